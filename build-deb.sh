@@ -23,6 +23,9 @@ mkdir -p "$PKGDIR/DEBIAN"
 mkdir -p "$PKGDIR/usr/bin"
 mkdir -p "$PKGDIR/usr/share/applications"
 mkdir -p "$PKGDIR/usr/share/icons/hicolor/scalable/apps"
+mkdir -p "$PKGDIR/usr/libexec/cosmic-ext-applet-updates"
+mkdir -p "$PKGDIR/usr/share/polkit-1/actions"
+mkdir -p "$PKGDIR/usr/lib/systemd/system/system-update.target.wants"
 
 # Copy files
 cp target/release/cosmic-ext-applet-updates "$PKGDIR/usr/bin/"
@@ -32,6 +35,16 @@ cp com.vintagetechie.CosmicExtAppletUpdates.desktop "$PKGDIR/usr/share/applicati
 cp icons/hicolor/scalable/apps/tux-normal.svg "$PKGDIR/usr/share/icons/hicolor/scalable/apps/"
 cp icons/hicolor/scalable/apps/tux-alert.svg "$PKGDIR/usr/share/icons/hicolor/scalable/apps/"
 
+# Privileged helper for unattended security updates and its polkit action
+install -m755 target/release/cosmic-ext-applet-updates-helper "$PKGDIR/usr/libexec/cosmic-ext-applet-updates/"
+cp data/com.vintagetechie.CosmicExtAppletUpdates.policy "$PKGDIR/usr/share/polkit-1/actions/"
+
+# Offline update helper and its system-update.target unit
+install -m755 data/offline-update.sh "$PKGDIR/usr/libexec/cosmic-ext-applet-updates/offline-update"
+cp data/cosmic-ext-applet-updates-offline.service "$PKGDIR/usr/lib/systemd/system/"
+ln -sf ../cosmic-ext-applet-updates-offline.service \
+    "$PKGDIR/usr/lib/systemd/system/system-update.target.wants/cosmic-ext-applet-updates-offline.service"
+
 # Create control file
 cat > "$PKGDIR/DEBIAN/control" << CONTROL
 Package: cosmic-ext-applet-updates
@@ -40,6 +53,7 @@ Section: utils
 Priority: optional
 Architecture: amd64
 Depends: cosmic-session
+Recommends: distro-info-data
 Maintainer: VintageTechie <https://vintagetechie.com\>
 Description: Updates Applet for COSMIC Desktop
  A community COSMIC Desktop applet that monitors package updates with support for
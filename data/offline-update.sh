@@ -0,0 +1,164 @@
+#!/bin/bash
+# Offline update helper for cosmic-ext-applet-updates
+#
+# Uses the systemd offline-update mechanism (systemd.offline-updates(7)):
+#   offline-update stage <apt|pacman>   Download updates and request an offline update
+#   offline-update cancel               Remove a pending offline update request
+#   offline-update run                  Install staged updates (run by system-update.target)
+#
+# stage and cancel are run through pkexec from the applet, run is started by
+# cosmic-ext-applet-updates-offline.service before the desktop comes up.
+
+set -u
+
+STATE_DIR="/var/lib/cosmic-ext-applet-updates"
+STAGE_DIR="$STATE_DIR/offline"
+RESULT_FILE="$STATE_DIR/offline-result"
+LOG_FILE="$STATE_DIR/offline-update.log"
+TRIGGER="/system-update"
+PACMAN_DB="/var/lib/pacman"
+
+die() {
+    echo "offline-update: $*" >&2
+    exit 1
+}
+
+stage() {
+    local backend="$1"
+
+    case "$backend" in
+        apt|pacman) ;;
+        *) die "unsupported backend '$backend'" ;;
+    esac
+
+    rm -rf "$STAGE_DIR"
+    mkdir -p -m 0755 "$STAGE_DIR" || die "cannot create $STAGE_DIR"
+
+    # Download everything now, the offline run has no network. The package
+    # list records what the offline run will install, one name per line.
+    case "$backend" in
+        apt)
+            apt-get update || die "apt-get update failed"
+            apt-get -d -y upgrade || die "download failed"
+            apt-get -s upgrade | awk '/^Inst / { print $2 }' > "$STAGE_DIR/packages" \
+                || die "cannot list staged packages"
+            ;;
+        pacman)
+            # Sync a private copy of the databases so the real sync database is
+            # untouched (no partial-upgrade state) until the offline run
+            local db="$STAGE_DIR/db"
+            mkdir -p "$db/sync" || die "cannot create $db"
+            ln -s "$PACMAN_DB/local" "$db/local" || die "cannot link local database"
+            cp -p "$PACMAN_DB"/sync/*.db "$db/sync/" 2>/dev/null
+            pacman -Sy --dbpath "$db" --logfile /dev/null || die "database sync failed"
+            pacman -Suw --noconfirm --dbpath "$db" --logfile /dev/null || die "download failed"
+            pacman -Qu --dbpath "$db" | awk '{ print $1 }' > "$STAGE_DIR/packages"
+            ;;
+    esac
+
+    echo "$backend" > "$STAGE_DIR/backend"
+    ln -sfn "$STAGE_DIR" "$TRIGGER" || die "cannot create $TRIGGER"
+}
+
+cancel() {
+    if [ "$(readlink "$TRIGGER" 2>/dev/null)" = "$STAGE_DIR" ]; then
+        rm -f "$TRIGGER"
+    fi
+    rm -rf "$STAGE_DIR"
+}
+
+write_result() {
+    local status="$1"
+    local message="$2"
+    local count=0
+
+    if [ -r "$STAGE_DIR/packages" ]; then
+        count=$(grep -c . "$STAGE_DIR/packages")
+    fi
+
+    cat > "$RESULT_FILE" << RESULT
+status=$status
+finished=$(date +%s)
+packages=$count
+message=$message
+RESULT
+    chmod 0644 "$RESULT_FILE"
+}
+
+restore_sync_db() {
+    local backup="$1"
+
+    rm -f "$PACMAN_DB"/sync/*.db
+    cp -p "$backup"/*.db "$PACMAN_DB/sync/" \
+        || echo "offline-update: could not restore the sync databases" >> "$LOG_FILE"
+}
+
+run() {
+    # Only handle requests staged by this applet
+    [ "$(readlink "$TRIGGER" 2>/dev/null)" = "$STAGE_DIR" ] || exit 0
+
+    # Remove the trigger first so a failing update can never cause a reboot loop
+    rm -f "$TRIGGER"
+
+    local backend
+    backend=$(cat "$STAGE_DIR/backend" 2>/dev/null)
+
+    local rc
+    case "$backend" in
+        apt)
+            DEBIAN_FRONTEND=noninteractive apt-get -y --no-download \
+                -o Dpkg::Options::=--force-confdef \
+                -o Dpkg::Options::=--force-confold \
+                upgrade > "$LOG_FILE" 2>&1
+            rc=$?
+            ;;
+        pacman)
+            # Switch to the databases the packages were downloaded from, so the
+            # upgrade installs exactly what was staged, without a -y. The old
+            # databases are kept so a failed upgrade does not leave new sync
+            # databases with old packages (a partial upgrade).
+            local backup="$STAGE_DIR/sync-backup"
+            if ! mkdir -p "$backup" || ! cp -p "$PACMAN_DB"/sync/*.db "$backup/"; then
+                echo "offline-update: could not back up the sync databases" > "$LOG_FILE"
+                rc=1
+            elif ! cp -p "$STAGE_DIR"/db/sync/*.db "$PACMAN_DB/sync/"; then
+                echo "offline-update: could not install the staged databases" > "$LOG_FILE"
+                rc=1
+                restore_sync_db "$backup"
+            else
+                pacman -Su --noconfirm > "$LOG_FILE" 2>&1
+                rc=$?
+                [ "$rc" -eq 0 ] || restore_sync_db "$backup"
+            fi
+            ;;
+        *)
+            echo "unknown backend '$backend'" > "$LOG_FILE"
+            rc=1
+            ;;
+    esac
+
+    if [ "$rc" -eq 0 ]; then
+        write_result success "Updates installed"
+    else
+        write_result failure "Update failed (exit code $rc), see $LOG_FILE"
+    fi
+
+    rm -rf "$STAGE_DIR"
+    systemctl reboot
+}
+
+case "${1:-}" in
+    stage)
+        [ $# -eq 2 ] || die "usage: offline-update stage <apt|pacman>"
+        stage "$2"
+        ;;
+    cancel)
+        cancel
+        ;;
+    run)
+        run
+        ;;
+    *)
+        die "usage: offline-update {stage|cancel|run}"
+        ;;
+esac
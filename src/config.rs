@@ -1,3 +1,5 @@
+use crate::package_manager::strategy::UpgradeStrategy;
+use crate::package_manager::Package;
 use crate::utils;
 use serde::{Deserialize, Serialize};
 use std::fs;
@@ -10,18 +12,102 @@ pub struct Config {
     /// Whether to show desktop notifications when updates are available
     #[serde(default = "default_true")]
     pub enable_notifications: bool,
+    /// Which updates trigger a notification: "all", "major" (major/epoch bumps,
+    /// downgrades and security updates) or "security"
+    #[serde(default = "default_notification_policy")]
+    pub notification_policy: String,
     /// Number of updates required to mark notification as critical/urgent
     #[serde(default = "default_urgency_threshold")]
     pub urgency_threshold: u32,
     /// Preferred terminal emulator (auto-detected if not set or "auto")
     #[serde(default = "default_terminal")]
     pub terminal: String,
+    /// Skip scheduled update checks while on a metered connection
+    #[serde(default = "default_true")]
+    pub skip_checks_on_metered: bool,
+    /// Minimum battery charge (percent) required to upgrade when not on AC power (0 disables)
+    #[serde(default = "default_min_battery_percent")]
+    pub min_battery_percent: u32,
+    /// Download package files in the background after a check so upgrades only install
+    #[serde(default)]
+    pub predownload_updates: bool,
+    /// Postpone background downloads while on a metered connection
+    #[serde(default = "default_true")]
+    pub defer_downloads_on_metered: bool,
+    /// Install security updates without prompting through the privileged helper
+    #[serde(default)]
+    pub unattended_security_updates: bool,
+    /// Local hour (0-23) at which the unattended maintenance window opens
+    #[serde(default = "default_maintenance_window_start")]
+    pub maintenance_window_start: u32,
+    /// Local hour (0-23) at which the unattended maintenance window closes
+    #[serde(default = "default_maintenance_window_end")]
+    pub maintenance_window_end: u32,
+    /// Where to load Arch security advisories from: "auto", "arch-audit",
+    /// a security tracker JSON URL or a local file path
+    #[serde(default = "default_arch_security_source")]
+    pub arch_security_source: String,
+    /// Arch Linux news feed checked before upgrading (URL or local file path)
+    #[serde(default = "default_arch_news_url")]
+    pub arch_news_url: String,
+    /// AUR helper to use on Arch: "auto" (first installed of paru, yay, pikaur,
+    /// aura, trizen, pamac), "none" (pacman only) or a helper name
+    #[serde(default = "default_aur_helper")]
+    pub aur_helper: String,
+    /// AUR base URL for RPC metadata queries (a local stand-in can be used instead)
+    #[serde(default = "default_aur_url")]
+    pub aur_url: String,
+    /// Ask the AUR helper for new commits of VCS (-git, -svn, ...) packages (`-Qua --devel`)
+    #[serde(default)]
+    pub check_vcs_packages: bool,
+    /// Notify about VCS package updates (they change often, so this is off by default)
+    #[serde(default)]
+    pub notify_vcs_packages: bool,
+    /// How the Upgrade button runs each backend
+    #[serde(default)]
+    pub upgrade: UpgradeStrategy,
+    /// Updates to leave out of the count and notifications
+    #[serde(default)]
+    pub ignored_packages: Vec<IgnoreRule>,
+}
+
+/// An entry of the package ignore list
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IgnoreRule {
+    /// Package name or glob pattern (`*` and `?`), e.g. "linux-*"
+    pub pattern: String,
+    /// Limit the rule to "official", "aur" or a repository/suite name (empty matches any source)
+    #[serde(default)]
+    pub scope: String,
+    /// Why the package is ignored, shown next to it in the popup
+    #[serde(default)]
+    pub reason: String,
+}
+
+impl IgnoreRule {
+    /// Check whether the rule applies to a pending update
+    pub fn matches(&self, package: &Package) -> bool {
+        if !utils::glob_match(&self.pattern, &package.name) {
+            return false;
+        }
+
+        match self.scope.as_str() {
+            "" => true,
+            "official" => !package.is_aur,
+            "aur" => package.is_aur,
+            repository => package.repository.split(',').any(|r| r.trim() == repository),
+        }
+    }
 }
 
 fn default_true() -> bool {
     true
 }
 
+fn default_notification_policy() -> String {
+    "all".to_string()
+}
+
 fn default_urgency_threshold() -> u32 {
     10 // Default: consider critical when 10+ updates available
 }
@@ -30,18 +116,82 @@ fn default_terminal() -> String {
     "auto".to_string()
 }
 
+fn default_min_battery_percent() -> u32 {
+    30 // Default: refuse upgrades on battery below 30%
+}
+
+fn default_maintenance_window_start() -> u32 {
+    12 // Default: lunch break, when laptops are usually on but idle
+}
+
+fn default_maintenance_window_end() -> u32 {
+    14
+}
+
+fn default_arch_security_source() -> String {
+    "auto".to_string() // arch-audit if installed, otherwise security.archlinux.org
+}
+
+fn default_aur_helper() -> String {
+    "auto".to_string()
+}
+
+fn default_aur_url() -> String {
+    crate::package_manager::aur_rpc::DEFAULT_AUR_URL.to_string()
+}
+
+fn default_arch_news_url() -> String {
+    crate::news::DEFAULT_NEWS_URL.to_string()
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
             check_interval_minutes: 30,
             enable_notifications: true,
+            notification_policy: default_notification_policy(),
             urgency_threshold: 10,
             terminal: "auto".to_string(),
+            skip_checks_on_metered: true,
+            min_battery_percent: 30,
+            predownload_updates: false,
+            defer_downloads_on_metered: true,
+            unattended_security_updates: false,
+            maintenance_window_start: 12,
+            maintenance_window_end: 14,
+            arch_security_source: default_arch_security_source(),
+            arch_news_url: default_arch_news_url(),
+            aur_helper: default_aur_helper(),
+            aur_url: default_aur_url(),
+            check_vcs_packages: false,
+            notify_vcs_packages: false,
+            upgrade: UpgradeStrategy::default(),
+            ignored_packages: Vec::new(),
         }
     }
 }
 
 impl Config {
+    /// Check whether the given local hour falls inside the maintenance window
+    ///
+    /// Windows may wrap around midnight (e.g. 22 to 6). Equal start and end
+    /// hours mean the window is open all day.
+    pub fn in_maintenance_window(&self, hour: u32) -> bool {
+        let (start, end) = (self.maintenance_window_start, self.maintenance_window_end);
+        if start == end {
+            true
+        } else if start < end {
+            (start..end).contains(&hour)
+        } else {
+            hour >= start || hour < end
+        }
+    }
+
+    /// Find the first ignore rule that applies to a pending update
+    pub fn ignore_rule_for(&self, package: &Package) -> Option<&IgnoreRule> {
+        self.ignored_packages.iter().find(|rule| rule.matches(package))
+    }
+
     /// Get the path to the config file (checks new location, falls back to old)
     fn config_path() -> Option<std::path::PathBuf> {
         utils::get_app_file_path("config.toml")
@@ -49,17 +199,20 @@ impl Config {
 
     /// Load config from file, or create default if it doesn't exist
     pub fn load() -> Self {
-        if let Some(path) = Self::config_path() {
-            if path.exists() {
-                if let Ok(contents) = fs::read_to_string(&path) {
-                    if let Ok(config) = toml::from_str(&contents) {
-                        return config;
-                    }
-                }
-            }
-        }
-        // Return default if file doesn't exist or can't be read
-        Self::default()
+        Self::config_path()
+            .and_then(|path| Self::load_from(&path))
+            // Return default if file doesn't exist or can't be read
+            .unwrap_or_default()
+    }
+
+    /// Load config from the given file
+    ///
+    /// # Returns
+    /// * `Some(Config)` - The file was read and parsed
+    /// * `None` - The file is missing, unreadable or invalid
+    pub fn load_from(path: &std::path::Path) -> Option<Self> {
+        let contents = fs::read_to_string(path).ok()?;
+        toml::from_str(&contents).ok()
     }
 
     /// Save config to file
@@ -0,0 +1,114 @@
+//! Privileged helper for the COSMIC Updates Applet
+//!
+//! Runs as root through pkexec (see the polkit action shipped in `data/`) and
+//! installs pending security updates without prompting. The helper takes no
+//! package names from the caller: it detects the backend, checks for updates
+//! itself and only upgrades packages the backend classifies as security updates.
+//! Held packages and updates matched by the calling user's ignore list are left
+//! alone. Only APT is supported, pacman cannot upgrade a subset of packages.
+//!
+//! Output protocol (stdout, one line per package):
+//!   upgraded <name> <current_version> <new_version>
+// Shared with the applet, which uses the rest of their API
+#[allow(dead_code)]
+mod config;
+#[allow(dead_code)]
+mod news;
+#[allow(dead_code)]
+mod package_manager;
+#[allow(dead_code)]
+mod utils;
+
+use std::os::unix::fs::MetadataExt;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let command = std::env::args().nth(1).unwrap_or_default();
+
+    let result = match command.as_str() {
+        "security-upgrade" => security_upgrade().await,
+        _ => Err("usage: cosmic-ext-applet-updates-helper security-upgrade".to_string()),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Install all pending security updates non-interactively
+async fn security_upgrade() -> Result<(), String> {
+    // /proc/self is owned by the effective user of the process
+    let euid = std::fs::metadata("/proc/self")
+        .map(|m| m.uid())
+        .map_err(|e| format!("Failed to determine user: {}", e))?;
+    if euid != 0 {
+        return Err("The helper must be run as root (via pkexec)".to_string());
+    }
+
+    // Only official packages are installed, and AUR helpers must not run as root
+    let pm = package_manager::detect_package_manager("none")
+        .ok_or("No supported package manager found")?;
+    if !matches!(pm, package_manager::PackageManager::Apt(_)) {
+        return Err("Unattended security updates are only supported with APT".to_string());
+    }
+
+    // Skip what the user chose not to upgrade, as the applet does
+    let config = caller_config();
+    let security: Vec<package_manager::Package> = pm
+        .check_updates(false)
+        .await?
+        .into_iter()
+        .filter(|p| p.is_security && !p.is_aur)
+        .filter(|p| p.hold.is_empty() && config.ignore_rule_for(p).is_none())
+        .collect();
+
+    if security.is_empty() {
+        return Ok(());
+    }
+
+    let names = security.iter().map(|p| p.name.clone()).collect();
+    pm.upgrade_packages(names).await?;
+
+    for package in &security {
+        println!(
+            "upgraded {} {} {}",
+            package.name, package.current_version, package.new_version
+        );
+    }
+
+    Ok(())
+}
+
+/// Load the configuration of the user who ran pkexec
+///
+/// pkexec clears the environment, so the user's config file is found through
+/// `PKEXEC_UID` and their home directory in /etc/passwd. Without it (or
+/// without a config file) the defaults apply, which ignore nothing.
+fn caller_config() -> config::Config {
+    let Ok(uid) = std::env::var("PKEXEC_UID") else {
+        return config::Config::default();
+    };
+
+    let home = std::fs::read_to_string("/etc/passwd").ok().and_then(|passwd| {
+        passwd.lines().find_map(|line| {
+            let fields: Vec<&str> = line.split(':').collect();
+            (fields.get(2) == Some(&uid.as_str())).then(|| fields.get(5).map(PathBuf::from))?
+        })
+    });
+
+    // Same locations as the applet: the current name first, then the old one
+    home.and_then(|home| {
+        ["cosmic-ext-applet-updates", "cosmic-updates"]
+            .iter()
+            .map(|dir| home.join(".config").join(dir).join("config.toml"))
+            .find(|path| path.exists())
+    })
+    .and_then(|path| config::Config::load_from(&path))
+    .unwrap_or_default()
+}
@@ -3,11 +3,16 @@
 //! This applet monitors package updates and displays them in the COSMIC panel.
 //! It provides a visual indicator when updates are available and allows one-click upgrades.
 mod config;
+mod news;
+mod offline;
 mod package_manager;
+mod power;
+mod release_upgrade;
 mod state;
+mod unattended;
 mod utils;
 
-use config::Config;
+use config::{Config, IgnoreRule};
 use state::State;
 use cosmic::app::{Core, Task};
 use cosmic::iced::platform_specific::shell::commands::popup::{destroy_popup, get_popup};
@@ -15,12 +20,21 @@ use cosmic::iced::{Alignment, Length, Limits, Subscription};
 use cosmic::iced_core::window;
 use cosmic::iced_runtime::core::window::Id as WindowId;
 use cosmic::{widget, Application, Element};
+use package_manager::aur_rpc::{self, ForeignPackage};
+use package_manager::changelog::ChangelogEntry;
+use package_manager::details::PackageDetails;
+use package_manager::repo_health::{self, IssueKind, RepositoryIssue};
+use package_manager::sources::{self, Source};
+use package_manager::version::Bump;
 use package_manager::{Package, PackageManager};
+use release_upgrade::ReleaseUpgrade;
+use std::collections::HashSet;
 use std::time::Duration;
 
 // For loading SVG icons with colors
 const ICON_NORMAL: &[u8] = include_bytes!("../icons/hicolor/scalable/apps/tux-normal.svg");
 const ICON_ALERT: &[u8] = include_bytes!("../icons/hicolor/scalable/apps/tux-alert.svg");
+const ICON_SECURITY: &[u8] = include_bytes!("../icons/hicolor/scalable/apps/tux-security.svg");
 
 /// Unique application identifier for the COSMIC desktop
 const APP_ID: &str = "com.vintagetechie.CosmicExtAppletUpdates";
@@ -34,6 +48,15 @@ const MIN_URGENCY_THRESHOLD: u32 = 1;
 /// Maximum urgency threshold value
 const MAX_URGENCY_THRESHOLD: u32 = 999;
 
+/// Minimum battery percentage setting (0 disables the battery guard)
+const MIN_BATTERY_PERCENT: u32 = 0;
+
+/// Maximum battery percentage setting
+const MAX_BATTERY_PERCENT: u32 = 100;
+
+/// Seconds between checks for a new distribution release (they query remote servers)
+const RELEASE_CHECK_INTERVAL: i64 = 24 * 60 * 60;
+
 /// Entry point for the applet
 fn main() -> cosmic::iced::Result {
     cosmic::applet::run::<UpdateChecker>(())
@@ -44,31 +67,90 @@ struct UpdateChecker {
     core: Core,
     popup: Option<WindowId>,
     packages: Vec<Package>,
+    /// Pending updates that are held or matched by the ignore list (not counted or notified)
+    ignored: Vec<Package>,
+    /// Whether the "Ignored" section of the main view is expanded
+    show_ignored: bool,
+    /// APT updates that are still being phased in (skipped by `apt upgrade`)
+    phasing: Vec<Package>,
+    /// APT updates kept back because they need new packages or removals
+    kept_back: Vec<Package>,
+    /// AUR VCS packages with new upstream commits (only with the VCS check enabled)
+    vcs: Vec<Package>,
+    /// Kept-back package to install (None for a full upgrade) once the power policy allows it
+    kept_back_target: Option<String>,
+    /// Installed packages the system sync database is ahead of (Arch partial-sync state)
+    out_of_sync: usize,
+    /// Installed foreign packages with an AUR maintenance problem
+    foreign_issues: Vec<ForeignPackage>,
+    /// Problems the last sync or refresh reported per repository (expired keys, 404s, ...)
+    repository_issues: Vec<RepositoryIssue>,
+    /// Configured package sources for the Package Sources page (None while loading)
+    sources: Option<Vec<Source>>,
+    /// New distribution release or end of support of the installed one
+    release_upgrade: Option<ReleaseUpgrade>,
+    /// When the last release check started (Unix seconds), 0 to check with the next update check
+    release_checked_at: i64,
     checking: bool,
     upgrading: bool,
     refreshing_cache: bool,
+    /// The cache refresh just synced the databases, so the check it starts skips its own sync
+    databases_synced: bool,
     checking_upgrade_status: bool,
+    downloading: bool,
+    staging_offline: bool,
+    running_unattended: bool,
+    checking_news: bool,
+    /// Arch news published since the last upgrade, shown before upgrading
+    news: Vec<news::NewsItem>,
+    /// True while an upgrade waits for the user to acknowledge manual-intervention news
+    news_needs_ack: bool,
+    /// True when an offline update is staged for the next reboot
+    offline_staged: bool,
+    /// Result of an offline update that ran since the user last looked
+    offline_result: Option<offline::OfflineResult>,
+    /// Package versions (name, new version) already fetched into the package cache
+    downloaded: HashSet<(String, String)>,
     error: Option<String>,
     package_manager: Option<PackageManager>,
     config: Config,
     pending_config: Config,
     interval_options: Vec<String>,
-    showing_settings: bool,
+    notification_policy_options: Vec<String>,
+    /// "auto", every supported AUR helper and "none", for the helper dropdown
+    aur_helper_options: Vec<String>,
+    page: Page,
+    /// Changelog for the package detail page (None while loading)
+    changelog: Option<Result<Vec<ChangelogEntry>, String>>,
+    /// Metadata for the package detail page (None while loading)
+    details: Option<Result<PackageDetails, String>>,
+    /// PKGBUILD/install script diff for an AUR package on the detail page (None while loading)
+    pkgbuild_diff: Option<Result<String, String>>,
     state: State,
     threshold_input_value: String,
+    battery_input_value: String,
+    /// Ignore rule being added on the ignore list page
+    ignore_pattern_input: String,
+    ignore_scope_input: String,
+    /// Reason for a new ignore rule (ignore list page and package detail page)
+    ignore_reason_input: String,
+    /// Why the last scheduled check or upgrade was held back (power/network policy)
+    deferred: Option<String>,
     /// Tracks the last applied check interval to detect changes
     last_applied_interval: u64,
 }
 
 impl Default for UpdateChecker {
     fn default() -> Self {
-        let package_manager = package_manager::detect_package_manager();
         let config = Config::load();
+        let package_manager = package_manager::detect_package_manager(&config.aur_helper);
         let state = State::load();
+        let offline_result = offline::read_result()
+            .filter(|result| result.finished > state.last_offline_result_seen);
 
         // Set initial error if no package manager found
         let initial_error = if package_manager.is_none() {
-            Some("No supported package manager found. Please install apt, pacman, paru, or yay.".to_string())
+            Some("No supported package manager found. Please install apt or pacman.".to_string())
         } else {
             None
         };
@@ -77,10 +159,32 @@ impl Default for UpdateChecker {
             core: Core::default(),
             popup: None,
             packages: Vec::new(),
+            ignored: Vec::new(),
+            show_ignored: false,
+            phasing: Vec::new(),
+            kept_back: Vec::new(),
+            vcs: Vec::new(),
+            kept_back_target: None,
+            out_of_sync: 0,
+            foreign_issues: Vec::new(),
+            repository_issues: Vec::new(),
+            sources: None,
+            release_upgrade: None,
+            release_checked_at: 0,
             checking: false,
             upgrading: false,
             refreshing_cache: false,
+            databases_synced: false,
             checking_upgrade_status: false,
+            downloading: false,
+            staging_offline: false,
+            running_unattended: false,
+            checking_news: false,
+            news: Vec::new(),
+            news_needs_ack: false,
+            offline_staged: offline::is_staged(),
+            offline_result,
+            downloaded: HashSet::new(),
             error: initial_error,
             package_manager,
             config: config.clone(),
@@ -96,14 +200,64 @@ impl Default for UpdateChecker {
                 "90 minutes".to_string(),
                 "120 minutes".to_string(),
             ],
-            showing_settings: false,
+            notification_policy_options: vec![
+                "All updates".to_string(),
+                "Major or security".to_string(),
+                "Security only".to_string(),
+            ],
+            aur_helper_options: std::iter::once("auto")
+                .chain(package_manager::aur_helper::HELPERS.iter().map(|helper| helper.binary))
+                .chain(std::iter::once("none"))
+                .map(|name| name.to_string())
+                .collect(),
+            page: Page::Main,
+            changelog: None,
+            details: None,
+            pkgbuild_diff: None,
             state,
             threshold_input_value: config.urgency_threshold.to_string(),
+            battery_input_value: config.min_battery_percent.to_string(),
+            ignore_pattern_input: String::new(),
+            ignore_scope_input: String::new(),
+            ignore_reason_input: String::new(),
+            deferred: None,
             last_applied_interval: config.check_interval_minutes,
         }
     }
 }
 
+/// Pages the popup can display
+#[derive(Debug, Clone, PartialEq)]
+enum Page {
+    /// Update list and actions
+    Main,
+    /// Settings form
+    Settings,
+    /// Ignore list editor, reached from the settings page
+    IgnoreList,
+    /// Package source inventory, reached from the settings page
+    Sources,
+    /// Detail page for the named package
+    PackageDetail(String),
+}
+
+/// Actions that are subject to the power and metered-connection policies
+#[derive(Debug, Clone, Copy)]
+enum GuardedAction {
+    /// Periodic update check triggered by the timer
+    ScheduledCheck,
+    /// User-requested upgrade
+    Upgrade,
+    /// Pre-download of pending updates after a successful check
+    BackgroundDownload,
+    /// Unattended installation of security updates in the maintenance window
+    UnattendedUpgrade,
+    /// User-requested full upgrade or install of a kept-back package
+    KeptBackUpgrade,
+    /// User-requested upgrade to a new distribution release
+    ReleaseUpgrade,
+}
+
 /// Messages that the applet can send to itself
 #[derive(Debug, Clone)]
 enum Message {
@@ -113,34 +267,139 @@ enum Message {
     OpenSettings,
     /// Close settings view (back to main)
     CloseSettings,
+    /// Open the detail page for a package
+    OpenPackage(String),
+    /// Close the package detail page (back to main)
+    ClosePackage,
+    /// Changelog loaded for the named package
+    ChangelogLoaded(String, Result<Vec<ChangelogEntry>, String>),
+    /// Package metadata loaded for the named package
+    DetailsLoaded(String, Result<PackageDetails, String>),
+    /// PKGBUILD diff loaded for the named AUR package
+    PkgbuildDiffLoaded(String, Result<String, String>),
     /// Notification that a popup window was closed
     PopupClosed(WindowId),
     /// Request to check for available updates
     CheckForUpdates,
-    /// Result of checking for updates (success with package list, or error)
-    UpdatesFound(Result<Vec<Package>, String>),
+    /// Result of checking for updates (success with package list and any repository
+    /// problems found while syncing, or error)
+    UpdatesFound(Result<(Vec<Package>, Option<Vec<RepositoryIssue>>), String>),
+    /// Number of packages the system sync database is ahead of
+    SyncStateChecked(usize),
+    /// Installed foreign packages that are deleted, orphaned or out of date in the AUR
+    ForeignPackagesAudited(Result<Vec<ForeignPackage>, String>),
     /// Request to start the upgrade process
     Upgrade,
     /// Result of starting the upgrade (success or error launching terminal)
     UpgradeStarted(Result<(), String>),
+    /// Result of fetching Arch news before an upgrade
+    NewsChecked(Result<Vec<news::NewsItem>, String>),
+    /// User read the manual-intervention news and wants to continue upgrading
+    AcknowledgeNews,
+    /// Hide the news list (cancels a blocked upgrade)
+    DismissNews,
+    /// Open a link in the default browser
+    OpenUrl(String),
     /// Poll to check if upgrade is still running
     CheckUpgradeStatus,
     /// Result of checking upgrade status (true if still running)
     UpgradeStatusChecked(bool),
     /// Request to refresh the cache
     RefreshCache,
-    /// Result of refreshing the cache
-    CacheRefreshed(Result<(), String>),
+    /// Result of refreshing the cache, with the problems reported per repository
+    CacheRefreshed(Result<Vec<RepositoryIssue>, String>),
+    /// Stage the pending updates for installation on next reboot
+    InstallOnRestart,
+    /// Result of staging an offline update
+    OfflineStaged(Result<(), String>),
+    /// Cancel a staged offline update
+    CancelOfflineUpdate,
+    /// Result of cancelling an offline update
+    OfflineCancelled(Result<(), String>),
+    /// Hide the result of the last offline update
+    DismissOfflineResult,
+    /// Result of an unattended security upgrade run by the privileged helper
+    UnattendedUpgradeFinished(Result<state::HistoryEntry, String>),
+    /// Result of a background download (the package versions that were fetched)
+    UpdatesDownloaded(Result<Vec<(String, String)>, String>),
     /// Periodic tick for scheduled update checks
     Tick,
+    /// Power and connection status read before running a guarded action
+    PowerStatusRead(GuardedAction, power::PowerStatus),
     /// Update check interval in settings
     SetCheckInterval(u64),
     /// Toggle notifications on/off
     ToggleNotifications(bool),
+    /// Which updates trigger a notification ("all", "major" or "security")
+    SetNotificationPolicy(String),
+    /// Set the preferred AUR helper ("auto", "none" or a helper name)
+    SetAurHelper(String),
+    /// Use `apt full-upgrade` instead of `apt upgrade`
+    ToggleAptFullUpgrade(bool),
+    /// Skip confirmation prompts for the current backend's upgrade
+    ToggleUpgradeNoConfirm(bool),
+    /// Pass `--devel` to the AUR helper
+    ToggleAurDevel(bool),
+    /// Toggle the VCS package check (`-Qua --devel`)
+    ToggleCheckVcs(bool),
+    /// Toggle notifications for VCS package updates
+    ToggleNotifyVcs(bool),
+    /// Only upgrade repository packages with the AUR helper
+    ToggleSkipAur(bool),
+    /// Extra upgrade flags for the current backend (input string)
+    SetUpgradeExtraFlags(String),
+    /// Expand or collapse the "Ignored" section
+    ToggleShowIgnored,
+    /// Open the ignore list editor from the settings page
+    OpenIgnoreList,
+    /// Return from the ignore list editor to the settings page
+    CloseIgnoreList,
+    /// Pattern of the ignore rule being added
+    SetIgnorePattern(String),
+    /// Scope of the ignore rule being added
+    SetIgnoreScope(String),
+    /// Reason of the ignore rule being added
+    SetIgnoreReason(String),
+    /// Add the rule from the ignore list editor to the pending config
+    AddIgnoreRule,
+    /// Remove a rule from the pending config by index
+    RemoveIgnoreRule(usize),
+    /// Ignore the named package right away (package detail page)
+    IgnorePackage(String),
+    /// Open the package source inventory from the settings page
+    OpenSources,
+    /// Return from the package source inventory to the settings page
+    CloseSources,
+    /// Configured package sources were read
+    SourcesLoaded(Vec<Source>),
+    /// Enable (true) or disable (false) the source at this index of the inventory
+    SetSourceEnabled(usize, bool),
+    /// Result of enabling or disabling a source
+    SourceChanged(Result<(), String>),
+    /// Hold (true) or release (false) the named package with the distro's mechanism
+    SetHold(String, bool),
+    /// Result of changing a distro-level hold
+    HoldChanged(Result<(), String>),
+    /// Install a kept-back package (or run a full upgrade for None) in a terminal
+    UpgradeKeptBack(Option<String>),
+    /// Result of checking for a new distribution release
+    ReleaseUpgradeChecked(Result<Option<ReleaseUpgrade>, String>),
+    /// Run the distribution's release upgrade tool (or open its guide)
+    StartReleaseUpgrade,
+    /// Result of launching the release upgrade tool
+    ReleaseUpgradeStarted(Result<(), String>),
     /// Update urgency threshold (input string)
     SetUrgencyThreshold(String),
     /// Update terminal preference (input string)
     SetTerminal(String),
+    /// Toggle skipping scheduled checks on metered connections
+    ToggleSkipChecksOnMetered(bool),
+    /// Toggle background pre-download of updates
+    TogglePredownload(bool),
+    /// Toggle unattended security updates
+    ToggleUnattendedSecurity(bool),
+    /// Update minimum battery percentage for upgrades (input string)
+    SetMinBatteryPercent(String),
     /// Save settings
     SaveSettings,
 }
@@ -214,19 +473,91 @@ impl Application for UpdateChecker {
                 // Reset pending config and show settings view
                 self.pending_config = self.config.clone();
                 self.threshold_input_value = self.config.urgency_threshold.to_string();
-                self.showing_settings = true;
+                self.battery_input_value = self.config.min_battery_percent.to_string();
+                self.page = Page::Settings;
                 Task::none()
             }
             Message::CloseSettings => {
                 // Go back to main view
-                self.showing_settings = false;
+                self.page = Page::Main;
+                Task::none()
+            }
+            Message::OpenPackage(name) => {
+                let Some(package) = self.find_package(&name).cloned() else {
+                    return Task::none();
+                };
+                self.page = Page::PackageDetail(name.clone());
+                self.ignore_reason_input.clear();
+                self.error = None;
+                self.changelog = None;
+                self.details = None;
+                self.pkgbuild_diff = None;
+
+                if let Some(pm) = &self.package_manager {
+                    let changelog_pm = pm.clone();
+                    let changelog_package = package.clone();
+                    let changelog_name = name.clone();
+                    let details_pm = pm.clone();
+                    let details_package = package.clone();
+                    let details_name = name.clone();
+
+                    let mut tasks = vec![
+                        Task::perform(
+                            async move { changelog_pm.changelog(&changelog_package).await },
+                            move |result| {
+                                cosmic::Action::App(Message::ChangelogLoaded(changelog_name.clone(), result))
+                            },
+                        ),
+                        Task::perform(async move { details_pm.details(&details_package).await }, move |result| {
+                            cosmic::Action::App(Message::DetailsLoaded(details_name.clone(), result))
+                        }),
+                    ];
+
+                    // AUR packages are built from a PKGBUILD that should be reviewed first
+                    if package.is_aur {
+                        let diff_pm = pm.clone();
+                        tasks.push(Task::perform(
+                            async move { diff_pm.pkgbuild_diff(&package).await },
+                            move |result| cosmic::Action::App(Message::PkgbuildDiffLoaded(name.clone(), result)),
+                        ));
+                    }
+
+                    Task::batch(tasks)
+                } else {
+                    Task::none()
+                }
+            }
+            Message::ClosePackage => {
+                self.page = Page::Main;
+                self.changelog = None;
+                self.details = None;
+                self.pkgbuild_diff = None;
+                Task::none()
+            }
+            Message::ChangelogLoaded(name, result) => {
+                // Ignore results for a page the user already left
+                if self.page == Page::PackageDetail(name) {
+                    self.changelog = Some(result);
+                }
+                Task::none()
+            }
+            Message::DetailsLoaded(name, result) => {
+                if self.page == Page::PackageDetail(name) {
+                    self.details = Some(result);
+                }
+                Task::none()
+            }
+            Message::PkgbuildDiffLoaded(name, result) => {
+                if self.page == Page::PackageDetail(name) {
+                    self.pkgbuild_diff = Some(result);
+                }
                 Task::none()
             }
             Message::PopupClosed(id) => {
                 // Clear popup reference if it matches the closed window
                 if self.popup.as_ref() == Some(&id) {
                     self.popup = None;
-                    self.showing_settings = false; // Reset settings view when popup closes
+                    self.page = Page::Main; // Reset to main view when popup closes
                 }
                 Task::none()
             }
@@ -236,39 +567,209 @@ impl Application for UpdateChecker {
                     return Task::none();
                 }
 
+                // Only the check right after a cache refresh may skip the sync
+                let sync = !std::mem::take(&mut self.databases_synced);
+
                 if let Some(pm) = &self.package_manager {
                     self.checking = true;
                     self.error = None;
                     let pm = pm.clone();
-                    Task::perform(async move { pm.check_updates().await }, |result| {
-                        cosmic::Action::App(Message::UpdatesFound(result))
-                    })
+                    let security_source = self.config.arch_security_source.clone();
+                    let check_vcs = self.config.check_vcs_packages;
+                    let aur_url = self.config.aur_url.clone();
+                    let sync_pm = pm.clone();
+                    let is_arch = pm.is_arch();
+
+                    let mut tasks = vec![
+                        Task::perform(
+                            {
+                                let aur_url = aur_url.clone();
+                                async move {
+                                    let issues = if sync { pm.sync_databases().await? } else { None };
+                                    let packages = pm.check_updates(check_vcs).await?;
+                                    // Correlate Arch updates with the security tracker and the AUR
+                                    if pm.is_arch() {
+                                        let packages =
+                                            package_manager::arch_security::annotate(packages, security_source).await;
+                                        Ok((package_manager::aur_rpc::annotate(packages, aur_url).await, issues))
+                                    } else {
+                                        Ok((packages, issues))
+                                    }
+                                }
+                            },
+                            |result| cosmic::Action::App(Message::UpdatesFound(result)),
+                        ),
+                        Task::perform(async move { sync_pm.out_of_sync_count().await }, |count| {
+                            cosmic::Action::App(Message::SyncStateChecked(count))
+                        }),
+                    ];
+
+                    // Foreign packages can be orphaned or deleted without ever showing an update
+                    if is_arch {
+                        tasks.push(Task::perform(package_manager::aur_rpc::audit_foreign(aur_url), |result| {
+                            cosmic::Action::App(Message::ForeignPackagesAudited(result))
+                        }));
+                    }
+
+                    // Arch is rolling; other releases are checked once a day
+                    let now = chrono::Local::now().timestamp();
+                    if !is_arch && now - self.release_checked_at >= RELEASE_CHECK_INTERVAL {
+                        self.release_checked_at = now;
+                        tasks.push(Task::perform(release_upgrade::check(), |result| {
+                            cosmic::Action::App(Message::ReleaseUpgradeChecked(result))
+                        }));
+                    }
+
+                    Task::batch(tasks)
                 } else {
                     self.error = Some("No package manager available".to_string());
                     Task::none()
                 }
             }
+            Message::ReleaseUpgradeChecked(result) => {
+                match result {
+                    Ok(upgrade) => self.release_upgrade = upgrade,
+                    // Keep the last result and try again with the next check
+                    Err(e) => {
+                        eprintln!("Warning: Could not check for a new release: {}", e);
+                        self.release_checked_at = 0;
+                    }
+                }
+                Task::none()
+            }
+            Message::StartReleaseUpgrade => {
+                // Same battery policy as the regular upgrade
+                Task::perform(power::read_status(), |status| {
+                    cosmic::Action::App(Message::PowerStatusRead(GuardedAction::ReleaseUpgrade, status))
+                })
+            }
+            Message::ReleaseUpgradeStarted(result) => {
+                if let Err(e) = result {
+                    self.error = Some(e);
+                }
+                Task::none()
+            }
+            Message::SyncStateChecked(count) => {
+                self.out_of_sync = count;
+                Task::none()
+            }
+            Message::ForeignPackagesAudited(result) => {
+                match result {
+                    Ok(foreign) => {
+                        self.foreign_issues = foreign;
+                        self.repository_issues
+                            .retain(|issue| issue.repository != repo_health::AUR_REPOSITORY);
+                    }
+                    // Keep the last known list when the audit fails
+                    Err(e) => {
+                        eprintln!("Warning: Could not audit foreign packages: {}", e);
+                        self.repository_issues
+                            .retain(|issue| issue.repository != repo_health::AUR_REPOSITORY);
+                        self.repository_issues.push(RepositoryIssue {
+                            kind: if repo_health::is_network_error(&e) {
+                                IssueKind::Unreachable
+                            } else {
+                                IssueKind::Other
+                            },
+                            repository: repo_health::AUR_REPOSITORY.to_string(),
+                            message: e,
+                            is_error: true,
+                        });
+                    }
+                }
+                Task::none()
+            }
             Message::UpdatesFound(result) => {
                 // Process the result of checking for updates
                 self.checking = false;
                 match result {
-                    Ok(packages) => {
-                        let new_count = packages.len();
-                        let old_count = self.state.last_update_count;
-                        
+                    Ok((packages, issues)) => {
+                        if let Some(issues) = issues {
+                            self.set_repository_issues(issues);
+                        }
+
+                        // Ignored updates are kept aside and left out of counts and notifications
                         self.packages = packages;
+                        self.ignored.clear();
+                        self.phasing.clear();
+                        self.kept_back.clear();
+                        self.vcs.clear();
+                        self.sort_updates();
                         self.error = None;
+
+                        let new_count = self.packages.len();
+                        let old_count = self.state.last_update_count;
+                        let new_security = self.security_count();
+                        let old_security = self.state.last_security_count;
                         
-                        // Send notification if enabled and count increased or went from 0 to any
-                        if self.config.enable_notifications && new_count > old_count {
-                            self.send_notification(new_count);
+                        // Send notification if enabled and count increased or went from 0 to any,
+                        // or if new security updates showed up, as long as the notification
+                        // policy considers at least one newly found update worth mentioning
+                        // (updates already reported at an earlier check, even before a restart, do not count)
+                        let pending: Vec<String> = self
+                            .packages
+                            .iter()
+                            .map(|p| format!("{} {}", p.name, p.new_version))
+                            .collect();
+                        let notable = self
+                            .packages
+                            .iter()
+                            .zip(&pending)
+                            .filter(|(_, key)| !self.state.notified_updates.contains(key))
+                            .any(|(p, _)| self.is_notable(p));
+                        if self.config.enable_notifications
+                            && notable
+                            && (new_count > old_count || new_security > old_security)
+                        {
+                            self.send_notification(new_count, new_security);
                         }
                         
-                        // Update state with new count
+                        // VCS packages notify on their own, and only when asked to
+                        let new_vcs = self.vcs.len();
+                        if self.config.enable_notifications
+                            && self.config.notify_vcs_packages
+                            && new_vcs > self.state.last_vcs_count
+                        {
+                            self.send_vcs_notification(new_vcs);
+                        }
+
+                        // Update state with new counts
                         self.state.last_update_count = new_count;
+                        self.state.last_security_count = new_security;
+                        self.state.last_vcs_count = new_vcs;
+                        self.state.notified_updates = pending;
                         if let Err(e) = self.state.save() {
                             eprintln!("Failed to save state: {}", e);
                         }
+
+                        // Install security updates on our own inside the maintenance window
+                        if !self.upgrading
+                            && !self.running_unattended
+                            && self.package_manager.as_ref().is_some_and(|pm| {
+                                unattended::is_due(pm, &self.config, &self.state, &self.packages)
+                            })
+                        {
+                            return Task::perform(power::read_status(), |status| {
+                                cosmic::Action::App(Message::PowerStatusRead(
+                                    GuardedAction::UnattendedUpgrade,
+                                    status,
+                                ))
+                            });
+                        }
+
+                        // Fetch the package files ahead of time if enabled
+                        if self.config.predownload_updates
+                            && !self.downloading
+                            && !self.upgrading
+                            && self.pending_download_count() > 0
+                        {
+                            return Task::perform(power::read_status(), |status| {
+                                cosmic::Action::App(Message::PowerStatusRead(
+                                    GuardedAction::BackgroundDownload,
+                                    status,
+                                ))
+                            });
+                        }
                     }
                     Err(e) => {
                         self.error = Some(e);
@@ -276,25 +777,28 @@ impl Application for UpdateChecker {
                 }
                 Task::none()
             }
+            Message::UpgradeKeptBack(name) => {
+                // Same battery policy as the regular upgrade
+                self.kept_back_target = name;
+                Task::perform(power::read_status(), |status| {
+                    cosmic::Action::App(Message::PowerStatusRead(GuardedAction::KeptBackUpgrade, status))
+                })
+            }
             Message::Upgrade => {
-                // Start the upgrade process using configured terminal
-                if let Some(pm) = &self.package_manager {
-                    self.error = None;
-                    let pm = pm.clone();
-                    let terminal = utils::get_terminal(&self.config.terminal);
-                    Task::perform(async move { pm.run_upgrade(&terminal).await }, |result| {
-                        cosmic::Action::App(Message::UpgradeStarted(result))
-                    })
-                } else {
-                    self.error = Some("No package manager available".to_string());
-                    Task::none()
-                }
+                // Check the battery policy before launching the upgrade
+                Task::perform(power::read_status(), |status| {
+                    cosmic::Action::App(Message::PowerStatusRead(GuardedAction::Upgrade, status))
+                })
             }
             Message::UpgradeStarted(result) => {
                 // Process the result of starting the upgrade
                 match result {
                     Ok(()) => {
                         self.upgrading = true;
+                        self.state.last_upgrade_time = chrono::Local::now().timestamp();
+                        if let Err(e) = self.state.save() {
+                            eprintln!("Failed to save state: {}", e);
+                        }
                         Task::none()
                     }
                     Err(e) => {
@@ -303,6 +807,55 @@ impl Application for UpdateChecker {
                     }
                 }
             }
+            Message::NewsChecked(result) => {
+                self.checking_news = false;
+                match result {
+                    Ok(items) => {
+                        let acknowledged_date = self.state.last_acknowledged_news_date;
+                        let acknowledged_guid = &self.state.last_acknowledged_news;
+                        self.news_needs_ack = items.iter().any(|item| {
+                            item.requires_manual_intervention()
+                                && item.published > acknowledged_date
+                                && &item.guid != acknowledged_guid
+                        });
+                        self.news = items;
+
+                        if self.news_needs_ack {
+                            // Wait for the user to read and acknowledge the news
+                            Task::none()
+                        } else {
+                            self.start_upgrade()
+                        }
+                    }
+                    Err(e) => {
+                        // A missing feed should not prevent upgrading
+                        eprintln!("Could not check Arch news: {}", e);
+                        self.start_upgrade()
+                    }
+                }
+            }
+            Message::AcknowledgeNews => {
+                if let Some(newest) = self.news.iter().max_by_key(|item| item.published) {
+                    self.state.last_acknowledged_news = newest.guid.clone();
+                    self.state.last_acknowledged_news_date = newest.published;
+                    if let Err(e) = self.state.save() {
+                        eprintln!("Failed to save state: {}", e);
+                    }
+                }
+                self.news_needs_ack = false;
+                self.start_upgrade()
+            }
+            Message::DismissNews => {
+                self.news.clear();
+                self.news_needs_ack = false;
+                Task::none()
+            }
+            Message::OpenUrl(url) => {
+                if let Err(e) = std::process::Command::new("xdg-open").arg(&url).spawn() {
+                    self.error = Some(format!("Failed to open {}: {}", url, e));
+                }
+                Task::none()
+            }
             Message::CheckUpgradeStatus => {
                 // Poll to see if upgrade is still running (guard against overlapping checks)
                 if self.checking_upgrade_status {
@@ -324,6 +877,7 @@ impl Application for UpdateChecker {
                 if !is_running && self.upgrading {
                     // Upgrade finished, refresh cache before checking for updates
                     self.upgrading = false;
+                    self.downloaded.clear();
                     self.update(Message::RefreshCache)
                 } else {
                     Task::none()
@@ -350,8 +904,10 @@ impl Application for UpdateChecker {
             Message::CacheRefreshed(result) => {
                 self.refreshing_cache = false;
                 match result {
-                    Ok(()) => {
-                        // Cache refreshed, now check for updates
+                    Ok(issues) => {
+                        // Cache refreshed, now check for updates without syncing again
+                        self.set_repository_issues(issues);
+                        self.databases_synced = true;
                         self.update(Message::CheckForUpdates)
                     }
                     Err(e) => {
@@ -360,14 +916,131 @@ impl Application for UpdateChecker {
                     }
                 }
             }
+            Message::InstallOnRestart => {
+                // Guard against staging twice
+                if self.staging_offline || self.offline_staged {
+                    return Task::none();
+                }
+
+                if let Some(pm) = &self.package_manager {
+                    self.staging_offline = true;
+                    self.error = None;
+                    let backend = pm.offline_backend();
+                    Task::perform(offline::stage(backend), |result| {
+                        cosmic::Action::App(Message::OfflineStaged(result))
+                    })
+                } else {
+                    self.error = Some("No package manager available".to_string());
+                    Task::none()
+                }
+            }
+            Message::OfflineStaged(result) => {
+                self.staging_offline = false;
+                match result {
+                    Ok(()) => self.offline_staged = offline::is_staged(),
+                    Err(e) => self.error = Some(e),
+                }
+                Task::none()
+            }
+            Message::CancelOfflineUpdate => {
+                Task::perform(offline::cancel(), |result| {
+                    cosmic::Action::App(Message::OfflineCancelled(result))
+                })
+            }
+            Message::OfflineCancelled(result) => {
+                if let Err(e) = result {
+                    self.error = Some(e);
+                }
+                self.offline_staged = offline::is_staged();
+                Task::none()
+            }
+            Message::DismissOfflineResult => {
+                if let Some(result) = self.offline_result.take() {
+                    self.state.last_offline_result_seen = result.finished;
+                    if let Err(e) = self.state.save() {
+                        eprintln!("Failed to save state: {}", e);
+                    }
+                }
+                Task::none()
+            }
+            Message::UnattendedUpgradeFinished(result) => {
+                self.running_unattended = false;
+                let entry = result.unwrap_or_else(|e| state::HistoryEntry {
+                    timestamp: chrono::Local::now().timestamp(),
+                    action: "Unattended security upgrade".to_string(),
+                    packages: Vec::new(),
+                    success: false,
+                    message: e,
+                });
+
+                if !entry.success {
+                    eprintln!("Unattended security upgrade failed: {}", entry.message);
+                } else if self.config.enable_notifications && !entry.packages.is_empty() {
+                    self.send_security_notification(&entry.message);
+                }
+
+                self.state.record(entry);
+                if let Err(e) = self.state.save() {
+                    eprintln!("Failed to save state: {}", e);
+                }
+
+                // Refresh the list so installed packages disappear
+                self.update(Message::CheckForUpdates)
+            }
+            Message::UpdatesDownloaded(result) => {
+                self.downloading = false;
+                match result {
+                    Ok(fetched) => {
+                        self.downloaded.extend(fetched);
+                    }
+                    Err(e) => {
+                        // Background work, so explain it in the popup instead of raising an error
+                        eprintln!("Background download failed: {}", e);
+                        self.deferred = Some("Background download failed, will retry after the next check".to_string());
+                    }
+                }
+                Task::none()
+            }
             Message::Tick => {
                 // Periodic check trigger (only if not currently upgrading)
                 if !self.upgrading {
-                    self.update(Message::CheckForUpdates)
+                    // Check the metered policy before touching the network
+                    Task::perform(power::read_status(), |status| {
+                        cosmic::Action::App(Message::PowerStatusRead(
+                            GuardedAction::ScheduledCheck,
+                            status,
+                        ))
+                    })
                 } else {
                     Task::none()
                 }
             }
+            Message::PowerStatusRead(action, status) => {
+                let deferral = match action {
+                    GuardedAction::ScheduledCheck => status.check_deferral(&self.config),
+                    GuardedAction::Upgrade
+                    | GuardedAction::UnattendedUpgrade
+                    | GuardedAction::KeptBackUpgrade
+                    | GuardedAction::ReleaseUpgrade => status.upgrade_deferral(&self.config),
+                    GuardedAction::BackgroundDownload => status.download_deferral(&self.config),
+                };
+
+                if let Some(reason) = deferral {
+                    // Record why the action was held back so the popup can explain it
+                    self.deferred = Some(reason);
+                    return Task::none();
+                }
+
+                self.deferred = None;
+                match action {
+                    GuardedAction::ScheduledCheck => self.update(Message::CheckForUpdates),
+                    GuardedAction::Upgrade => self.check_news_before_upgrade(),
+                    GuardedAction::BackgroundDownload => self.start_download(),
+                    GuardedAction::UnattendedUpgrade => self.start_unattended_upgrade(),
+                    GuardedAction::KeptBackUpgrade => self.start_kept_back_upgrade(),
+                    GuardedAction::ReleaseUpgrade => self.start_release_upgrade(),
+                }
+            }
             Message::SetCheckInterval(minutes) => {
                 self.pending_config.check_interval_minutes = minutes;
                 Task::none()
@@ -376,6 +1049,182 @@ impl Application for UpdateChecker {
                 self.pending_config.enable_notifications = enabled;
                 Task::none()
             }
+            Message::SetNotificationPolicy(policy) => {
+                self.pending_config.notification_policy = policy;
+                Task::none()
+            }
+            Message::SetAurHelper(helper) => {
+                self.pending_config.aur_helper = helper;
+                Task::none()
+            }
+            Message::ToggleAptFullUpgrade(enabled) => {
+                self.pending_config.upgrade.apt_full_upgrade = enabled;
+                Task::none()
+            }
+            Message::ToggleUpgradeNoConfirm(enabled) => {
+                let strategy = &mut self.pending_config.upgrade;
+                match &self.package_manager {
+                    Some(PackageManager::Apt(_)) => strategy.apt_assume_yes = enabled,
+                    Some(pm) if pm.has_aur_helper() => strategy.aur_noconfirm = enabled,
+                    _ => strategy.pacman_noconfirm = enabled,
+                }
+                Task::none()
+            }
+            Message::ToggleAurDevel(enabled) => {
+                self.pending_config.upgrade.aur_devel = enabled;
+                Task::none()
+            }
+            Message::ToggleCheckVcs(enabled) => {
+                self.pending_config.check_vcs_packages = enabled;
+                Task::none()
+            }
+            Message::ToggleNotifyVcs(enabled) => {
+                self.pending_config.notify_vcs_packages = enabled;
+                Task::none()
+            }
+            Message::ToggleSkipAur(enabled) => {
+                self.pending_config.upgrade.aur_skip = enabled;
+                Task::none()
+            }
+            Message::SetUpgradeExtraFlags(input) => {
+                let strategy = &mut self.pending_config.upgrade;
+                match &self.package_manager {
+                    Some(PackageManager::Apt(_)) => strategy.apt_extra_flags = input,
+                    Some(pm) if pm.has_aur_helper() => strategy.aur_extra_flags = input,
+                    _ => strategy.pacman_extra_flags = input,
+                }
+                Task::none()
+            }
+            Message::ToggleShowIgnored => {
+                self.show_ignored = !self.show_ignored;
+                Task::none()
+            }
+            Message::OpenIgnoreList => {
+                self.ignore_pattern_input.clear();
+                self.ignore_scope_input.clear();
+                self.ignore_reason_input.clear();
+                self.page = Page::IgnoreList;
+                Task::none()
+            }
+            Message::CloseIgnoreList => {
+                // Rules are saved together with the other settings
+                self.page = Page::Settings;
+                Task::none()
+            }
+            Message::SetIgnorePattern(input) => {
+                self.ignore_pattern_input = input;
+                Task::none()
+            }
+            Message::SetIgnoreScope(input) => {
+                self.ignore_scope_input = input;
+                Task::none()
+            }
+            Message::SetIgnoreReason(input) => {
+                self.ignore_reason_input = input;
+                Task::none()
+            }
+            Message::AddIgnoreRule => {
+                let pattern = self.ignore_pattern_input.trim();
+                if pattern.is_empty() {
+                    return Task::none();
+                }
+                self.pending_config.ignored_packages.push(IgnoreRule {
+                    pattern: pattern.to_string(),
+                    scope: self.ignore_scope_input.trim().to_lowercase(),
+                    reason: self.ignore_reason_input.trim().to_string(),
+                });
+                self.ignore_pattern_input.clear();
+                self.ignore_scope_input.clear();
+                self.ignore_reason_input.clear();
+                Task::none()
+            }
+            Message::RemoveIgnoreRule(index) => {
+                if index < self.pending_config.ignored_packages.len() {
+                    self.pending_config.ignored_packages.remove(index);
+                }
+                Task::none()
+            }
+            Message::OpenSources => {
+                self.page = Page::Sources;
+                self.sources = None;
+                self.load_sources()
+            }
+            Message::CloseSources => {
+                self.page = Page::Settings;
+                Task::none()
+            }
+            Message::SourcesLoaded(sources) => {
+                self.sources = Some(sources);
+                Task::none()
+            }
+            Message::SetSourceEnabled(index, enabled) => {
+                match self.sources.as_ref().and_then(|sources| sources.get(index)) {
+                    Some(source) => {
+                        let source = source.clone();
+                        self.error = None;
+                        Task::perform(async move { sources::set_enabled(source, enabled).await }, |result| {
+                            cosmic::Action::App(Message::SourceChanged(result))
+                        })
+                    }
+                    None => Task::none(),
+                }
+            }
+            Message::SourceChanged(result) => match result {
+                Ok(()) => {
+                    // Re-read the sources and re-check, the change affects the available updates
+                    Task::batch([self.load_sources(), self.update(Message::CheckForUpdates)])
+                }
+                Err(e) => {
+                    self.error = Some(e);
+                    Task::none()
+                }
+            },
+            Message::SetHold(name, hold) => {
+                if let Some(pm) = &self.package_manager {
+                    let pm = pm.clone();
+                    Task::perform(async move { pm.set_hold(name, hold).await }, |result| {
+                        cosmic::Action::App(Message::HoldChanged(result))
+                    })
+                } else {
+                    Task::none()
+                }
+            }
+            Message::HoldChanged(result) => {
+                match result {
+                    Ok(()) => {
+                        // Re-check so the hold state and counts are current
+                        self.page = Page::Main;
+                        self.changelog = None;
+                        self.details = None;
+                        self.update(Message::CheckForUpdates)
+                    }
+                    Err(e) => {
+                        self.error = Some(e);
+                        Task::none()
+                    }
+                }
+            }
+            Message::IgnorePackage(name) => {
+                // Saved immediately, as this happens outside the settings page
+                let mut config = self.config.clone();
+                config.ignored_packages.push(IgnoreRule {
+                    pattern: name,
+                    scope: String::new(),
+                    reason: self.ignore_reason_input.trim().to_string(),
+                });
+                if let Err(e) = config.save() {
+                    self.error = Some(format!("Failed to save settings: {}", e));
+                    return Task::none();
+                }
+                self.config = config;
+                self.pending_config = self.config.clone();
+                self.ignore_reason_input.clear();
+                self.sort_updates();
+                self.page = Page::Main;
+                self.changelog = None;
+                self.details = None;
+                Task::none()
+            }
             Message::SetUrgencyThreshold(input) => {
                 self.threshold_input_value = input.clone();
                 // Try to parse and validate, update config if valid
@@ -392,7 +1241,54 @@ impl Application for UpdateChecker {
                 self.pending_config.terminal = terminal;
                 Task::none()
             }
+            Message::ToggleSkipChecksOnMetered(enabled) => {
+                self.pending_config.skip_checks_on_metered = enabled;
+                Task::none()
+            }
+            Message::TogglePredownload(enabled) => {
+                self.pending_config.predownload_updates = enabled;
+                Task::none()
+            }
+            Message::ToggleUnattendedSecurity(enabled) => {
+                self.pending_config.unattended_security_updates = enabled;
+                Task::none()
+            }
+            Message::SetMinBatteryPercent(input) => {
+                self.battery_input_value = input.clone();
+                // Same approach as the urgency threshold: only apply valid values
+                if let Ok(value) = input.parse::<u32>() {
+                    if (MIN_BATTERY_PERCENT..=MAX_BATTERY_PERCENT).contains(&value) {
+                        self.pending_config.min_battery_percent = value;
+                    }
+                }
+                Task::none()
+            }
             Message::SaveSettings => {
+                // Refuse extra upgrade flags that are not on the allow-list (of the chosen AUR helper)
+                let aur_helper = self
+                    .package_manager
+                    .as_ref()
+                    .filter(|pm| pm.is_arch())
+                    .and_then(|_| package_manager::aur_helper::AurHelper::select(&self.pending_config.aur_helper));
+                if let Err(e) = self.pending_config.upgrade.validate(aur_helper) {
+                    self.error = Some(e);
+                    return Task::none();
+                }
+
+                // Validate the battery percentage before anything else
+                match self.battery_input_value.parse::<u32>() {
+                    Ok(value) if (MIN_BATTERY_PERCENT..=MAX_BATTERY_PERCENT).contains(&value) => {
+                        self.pending_config.min_battery_percent = value;
+                    }
+                    _ => {
+                        self.error = Some(format!(
+                            "Minimum battery must be a number between {} and {}",
+                            MIN_BATTERY_PERCENT, MAX_BATTERY_PERCENT
+                        ));
+                        return Task::none();
+                    }
+                }
+
                 // Validate the input string can be parsed as a valid number
                 match self.threshold_input_value.parse::<u32>() {
                     Ok(value) if (MIN_URGENCY_THRESHOLD..=MAX_URGENCY_THRESHOLD).contains(&value) => {
@@ -401,13 +1297,27 @@ impl Application for UpdateChecker {
                             self.error = Some(format!("Failed to save settings: {}", e));
                             Task::none()
                         } else {
+                            // Turning the VCS check on or off changes what a check returns
+                            let vcs_changed =
+                                self.config.check_vcs_packages != self.pending_config.check_vcs_packages;
+                            // A different AUR helper means a different backend
+                            let helper_changed = self.config.aur_helper != self.pending_config.aur_helper;
+                            if helper_changed {
+                                self.package_manager =
+                                    package_manager::detect_package_manager(&self.pending_config.aur_helper);
+                            }
                             // Apply the new config
                             self.config = self.pending_config.clone();
+                            self.sort_updates();
                             // Update last applied interval to trigger subscription refresh if changed
                             self.last_applied_interval = self.config.check_interval_minutes;
                             // Go back to main view
-                            self.showing_settings = false;
-                            Task::none()
+                            self.page = Page::Main;
+                            if vcs_changed || helper_changed {
+                                self.update(Message::CheckForUpdates)
+                            } else {
+                                Task::none()
+                            }
                         }
                     }
                     Ok(_) => {
@@ -462,8 +1372,7 @@ impl Application for UpdateChecker {
 
     /// Render the panel icon view
     fn view(&self) -> Element<'_, Self::Message> {
-        let count = self.packages.len();
-        let icon_data = if count > 0 { ICON_ALERT } else { ICON_NORMAL };
+        let icon_data = self.status_icon();
         let svg_handle = widget::svg::Handle::from_memory(icon_data);
 
         widget::container(
@@ -486,15 +1395,19 @@ impl Application for UpdateChecker {
             return widget::text("").into();
         }
 
-        // Show settings view or main view based on state
-        if self.showing_settings {
-            return self.settings_view();
+        // Show settings, package detail or main view based on state
+        match &self.page {
+            Page::Settings => return self.settings_view(),
+            Page::IgnoreList => return self.ignore_list_view(),
+            Page::Sources => return self.sources_view(),
+            Page::PackageDetail(name) => return self.package_view(name),
+            Page::Main => {}
         }
 
         let count = self.packages.len();
 
         // Choose icon for header
-        let header_icon_data = if count > 0 { ICON_ALERT } else { ICON_NORMAL };
+        let header_icon_data = self.status_icon();
         let header_icon = widget::svg(widget::svg::Handle::from_memory(header_icon_data))
             .width(Length::Fixed(32.0))
             .height(Length::Fixed(32.0));
@@ -567,6 +1480,26 @@ impl Application for UpdateChecker {
                 })
                 .spacing(6);
 
+            // Security updates get their own count
+            let security_count = self.security_count();
+            if security_count > 0 {
+                col = col.push(
+                    cosmic::iced::widget::rich_text(vec![
+                        cosmic::iced::widget::text::Span::new(format!("🛡 {} security", security_count))
+                            .color(cosmic::iced::Color::from_rgb(0.9, 0.3, 0.3)), // Red
+                    ])
+                    .size(13),
+                );
+            }
+
+            // Total download, known when the backend reads sizes from its database
+            let download_size: u64 = self.packages.iter().map(|p| p.download_size).sum();
+            if download_size > 0 {
+                col = col.push(
+                    widget::text(format!("⬇ {} to download", utils::format_size(download_size))).size(12),
+                );
+            }
+
             // Add package cards with colored text using rich_text and Spans
             for package in &self.packages {
                 use cosmic::iced::widget::text::Span;
@@ -581,6 +1514,13 @@ impl Application for UpdateChecker {
                     );
                 }
 
+                // Add shield badge for security updates
+                if package.is_security {
+                    spans.push(
+                        Span::new("🛡 ").color(cosmic::iced::Color::from_rgb(0.9, 0.3, 0.3)), // Red
+                    );
+                }
+
                 // Add version information with colors
                 spans.extend(vec![
                     Span::new(&package.current_version)
@@ -590,38 +1530,236 @@ impl Application for UpdateChecker {
                         .color(cosmic::iced::Color::from_rgb(0.3, 0.8, 0.3)), // Green
                 ]);
 
+                // Add version bump badge
+                if package.bump != Bump::Unknown {
+                    spans.push(Span::new(format!(" [{}]", package.bump.label())).color(bump_color(package.bump)));
+                }
+
+                // Flag AUR packages marked out of date by users
+                if package.aur_out_of_date.is_some() {
+                    spans.push(
+                        Span::new(" [out of date]").color(cosmic::iced::Color::from_rgb(0.9, 0.6, 0.2)), // Orange
+                    );
+                }
+
                 let package_text = cosmic::iced::widget::rich_text(spans).size(12);
 
-                let package_card = widget::container(package_text).padding(6);
+                // Clicking a card opens the package detail page
+                let package_card = widget::mouse_area(widget::container(package_text).padding(6))
+                    .on_press(Message::OpenPackage(package.name.clone()));
 
                 col = col.push(package_card);
             }
-            col
+
+            // Vulnerable packages whose pending update fixes known CVEs
+            let vulnerable: Vec<&Package> =
+                self.packages.iter().filter(|p| !p.cves.is_empty()).collect();
+            if !vulnerable.is_empty() {
+                col = col.push(
+                    widget::text("Vulnerable packages with fixes available").size(14),
+                );
+                for package in vulnerable {
+                    let severity = if package.severity.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" ({})", package.severity)
+                    };
+                    col = col.push(
+                        widget::container(
+                            widget::text(format!(
+                                "🛡 {}{}: this update fixes {}",
+                                package.name,
+                                severity,
+                                package.cves.join(", ")
+                            ))
+                            .size(12),
+                        )
+                        .padding(6),
+                    );
+                }
+            }
+            col
+        } else if self.release_upgrade.as_ref().is_some_and(|upgrade| upgrade.end_of_life) {
+            // No pending updates is expected once a release stops receiving them
+            widget::column()
+                .push(widget::text("⚠ No updates, but this release is no longer supported").size(15))
+                .spacing(4)
         } else {
             widget::column()
                 .push(widget::text("✓ System is up to date").size(15))
                 .spacing(4)
         };
 
+        // Warn when the system database was synced without upgrading (Arch)
+        let status_content = if self.out_of_sync > 0 && !self.upgrading && !self.checking {
+            status_content.push(
+                widget::text(format!(
+                    "⚠ The pacman database was synced without upgrading ({} packages behind). \
+                     Installing packages now would be a partial upgrade, run a full upgrade first.",
+                    self.out_of_sync
+                ))
+                .size(12),
+            )
+        } else {
+            status_content
+        };
+
+        // Broken repositories silently stop delivering updates, so they are listed
+        let status_content = if !self.repository_issues.is_empty() && !self.upgrading && !self.checking {
+            status_content.push(self.repositories_view())
+        } else {
+            status_content
+        };
+
+        // Installed AUR packages that are orphaned, deleted or flagged out of date
+        let status_content = if !self.foreign_issues.is_empty() && !self.upgrading && !self.checking {
+            status_content.push(self.foreign_issues_view())
+        } else {
+            status_content
+        };
+
+        // Kept-back updates need a full upgrade or an explicit install
+        let status_content = if !self.kept_back.is_empty() && !self.upgrading && !self.checking {
+            status_content.push(self.kept_back_view())
+        } else {
+            status_content
+        };
+
+        // VCS packages are listed separately so their frequent updates stay out of the count
+        let status_content = if !self.vcs.is_empty() && !self.upgrading && !self.checking {
+            status_content.push(self.vcs_view())
+        } else {
+            status_content
+        };
+
+        // Phased updates are listed, but not counted until APT offers them
+        let status_content = if !self.phasing.is_empty() && !self.upgrading && !self.checking {
+            status_content.push(self.phasing_view())
+        } else {
+            status_content
+        };
+
+        // Ignored updates stay visible, greyed out, in a collapsible section
+        let status_content = if !self.ignored.is_empty() && !self.upgrading && !self.checking {
+            status_content.push(self.ignored_view())
+        } else {
+            status_content
+        };
+
+        // Arch news since the last upgrade, with the acknowledgement gate
+        let status_content = if self.checking_news {
+            status_content.push(widget::text("📰 Checking Arch Linux news...").size(12))
+        } else if !self.news.is_empty() {
+            status_content.push(self.news_view())
+        } else {
+            status_content
+        };
+
+        // Report unattended security upgrades in progress
+        let status_content = if self.running_unattended {
+            status_content.push(widget::text("🛡 Installing security updates...").size(12))
+        } else {
+            status_content
+        };
+
+        // Report background download progress
+        let ready_count = self.downloaded_count();
+        let status_content = if self.downloading {
+            status_content.push(widget::text("📥 Downloading updates in the background...").size(12))
+        } else if ready_count > 0 && !self.upgrading {
+            status_content.push(
+                widget::text(format!(
+                    "📥 {} update{} downloaded, ready to install",
+                    ready_count,
+                    if ready_count == 1 { "" } else { "s" }
+                ))
+                .size(12),
+            )
+        } else {
+            status_content
+        };
+
+        // Offline update status: pending request, or the result after the reboot
+        let status_content = if self.staging_offline {
+            status_content.push(widget::text("🔁 Preparing updates for restart...").size(12))
+        } else if self.offline_staged {
+            status_content.push(
+                widget::row()
+                    .push(widget::text("🔁 Updates will be installed on next restart").size(12))
+                    .push(widget::horizontal_space())
+                    .push(widget::button::standard("Cancel").on_press(Message::CancelOfflineUpdate))
+                    .spacing(8)
+                    .align_y(Alignment::Center),
+            )
+        } else {
+            status_content
+        };
+
+        let status_content = if let Some(result) = &self.offline_result {
+            let summary = if result.success {
+                format!(
+                    "✓ {} update{} installed on restart",
+                    result.packages,
+                    if result.packages == 1 { "" } else { "s" }
+                )
+            } else {
+                format!("❌ Offline update failed: {}", result.message)
+            };
+            status_content.push(
+                widget::row()
+                    .push(widget::text(summary).size(12))
+                    .push(widget::horizontal_space())
+                    .push(widget::button::standard("Dismiss").on_press(Message::DismissOfflineResult))
+                    .spacing(8)
+                    .align_y(Alignment::Center),
+            )
+        } else {
+            status_content
+        };
+
+        // Explain any action held back by the power or metered-connection policy
+        let status_content = if let Some(reason) = &self.deferred {
+            status_content.push(widget::text(format!("⏸ {}", reason)).size(12))
+        } else {
+            status_content
+        };
+
         // Buttons row
         let buttons = widget::row()
             .push(widget::button::standard("Settings").on_press(Message::OpenSettings))
             .push(widget::button::standard("Check Now").on_press(Message::CheckForUpdates))
             .push(widget::horizontal_space())
-            .push(if count > 0 && !self.upgrading {
+            .push(if count > 0 && !self.upgrading && !self.checking_news && !self.news_needs_ack {
                 widget::button::suggested("Upgrade").on_press(Message::Upgrade)
             } else {
                 widget::button::suggested("Upgrade")
             })
             .spacing(12);
 
+        // Offline update action, shown separately to keep the main row compact
+        let offline_row = widget::row()
+            .push(widget::horizontal_space())
+            .push(
+                if count > 0 && !self.upgrading && !self.staging_offline && !self.offline_staged {
+                    widget::button::standard("Install on Restart").on_press(Message::InstallOnRestart)
+                } else {
+                    widget::button::standard("Install on Restart")
+                },
+            );
+
         // Wrap status content in scrollable with max height
         let scrollable_status = widget::scrollable(status_content).height(Length::Fixed(400.0));
 
+        // A new release or the end of support is shown above everything else
+        let mut main_column = widget::column().push(header);
+        if let Some(upgrade) = self.release_upgrade.as_ref().filter(|_| !self.upgrading) {
+            main_column = main_column.push(self.release_upgrade_view(upgrade));
+        }
+
         let content = self.core.applet.popup_container(
-            widget::column()
-                .push(header)
+            main_column
                 .push(scrollable_status)
+                .push(offline_row)
                 .push(buttons)
                 .spacing(12)
                 .padding(16)
@@ -633,17 +1771,243 @@ impl Application for UpdateChecker {
 }
 
 impl UpdateChecker {
+    /// Launch the upgrade in the configured terminal once the power policy allows it
+    fn start_upgrade(&mut self) -> Task<Message> {
+        if let Some(pm) = &self.package_manager {
+            self.error = None;
+            let pm = pm.clone();
+            let terminal = utils::get_terminal(&self.config.terminal);
+            let strategy = self.config.upgrade.clone();
+            Task::perform(async move { pm.run_upgrade(&terminal, &strategy).await }, |result| {
+                cosmic::Action::App(Message::UpgradeStarted(result))
+            })
+        } else {
+            self.error = Some("No package manager available".to_string());
+            Task::none()
+        }
+    }
+
+    /// Launch `apt full-upgrade` or `apt install <pkg>` for kept-back packages
+    fn start_kept_back_upgrade(&mut self) -> Task<Message> {
+        if let Some(pm) = &self.package_manager {
+            self.error = None;
+            let pm = pm.clone();
+            let terminal = utils::get_terminal(&self.config.terminal);
+            let name = self.kept_back_target.take();
+            Task::perform(
+                async move { pm.run_kept_back_upgrade(&terminal, name).await },
+                |result| cosmic::Action::App(Message::UpgradeStarted(result)),
+            )
+        } else {
+            self.error = Some("No package manager available".to_string());
+            Task::none()
+        }
+    }
+
+    /// Launch the distribution's release upgrade tool, or open the guide for manual upgrades
+    fn start_release_upgrade(&mut self) -> Task<Message> {
+        let Some(upgrade) = &self.release_upgrade else {
+            return Task::none();
+        };
+
+        if upgrade.command.is_empty() {
+            let guide = upgrade.guide.clone();
+            return self.update(Message::OpenUrl(guide));
+        }
+
+        self.error = None;
+        let terminal = utils::get_terminal(&self.config.terminal);
+        let command = upgrade.command.clone();
+        Task::perform(release_upgrade::run_upgrade(terminal, command), |result| {
+            cosmic::Action::App(Message::ReleaseUpgradeStarted(result))
+        })
+    }
+
+    /// Start downloading pending updates in the background once the policy allows it
+    fn start_download(&mut self) -> Task<Message> {
+        let Some(pm) = &self.package_manager else {
+            return Task::none();
+        };
+
+        self.downloading = true;
+        let pm = pm.clone();
+        // Remember which versions this download covers (AUR packages are built at install time)
+        let snapshot: Vec<(String, String)> = self
+            .packages
+            .iter()
+            .filter(|p| !p.is_aur)
+            .map(|p| (p.name.clone(), p.new_version.clone()))
+            .collect();
+
+        Task::perform(
+            async move { pm.download_updates().await.map(|()| snapshot) },
+            |result| cosmic::Action::App(Message::UpdatesDownloaded(result)),
+        )
+    }
+
+    /// Read the configured package sources for the Package Sources page
+    fn load_sources(&self) -> Task<Message> {
+        let Some(pm) = &self.package_manager else {
+            return Task::none();
+        };
+
+        let pm = pm.clone();
+        Task::perform(async move { pm.sources().await }, |sources| {
+            cosmic::Action::App(Message::SourcesLoaded(sources))
+        })
+    }
+
+    /// On Arch, read the news feed before upgrading; other backends upgrade directly
+    fn check_news_before_upgrade(&mut self) -> Task<Message> {
+        let is_arch = self.package_manager.as_ref().is_some_and(|pm| pm.is_arch());
+        if !is_arch {
+            return self.start_upgrade();
+        }
+
+        self.checking_news = true;
+        let url = self.config.arch_news_url.clone();
+        let since = self.state.last_upgrade_time;
+        Task::perform(news::fetch_since_last_upgrade(url, since), |result| {
+            cosmic::Action::App(Message::NewsChecked(result))
+        })
+    }
+
+    /// Run the privileged helper to install security updates without prompting
+    fn start_unattended_upgrade(&mut self) -> Task<Message> {
+        self.running_unattended = true;
+        self.state.last_unattended_attempt = chrono::Local::now().timestamp();
+        if let Err(e) = self.state.save() {
+            eprintln!("Failed to save state: {}", e);
+        }
+
+        Task::perform(unattended::run_security_upgrade(), |result| {
+            cosmic::Action::App(Message::UnattendedUpgradeFinished(result))
+        })
+    }
+
+    /// Replace the repository problems from a sync or refresh
+    ///
+    /// The AUR's entry comes from the foreign package audit and is kept.
+    fn set_repository_issues(&mut self, issues: Vec<RepositoryIssue>) {
+        self.repository_issues
+            .retain(|issue| issue.repository == repo_health::AUR_REPOSITORY);
+        self.repository_issues.extend(issues);
+    }
+
+    /// Sort pending updates into the main list and the lists shown set aside
+    ///
+    /// Packages held by the distro (apt-mark hold, IgnorePkg) or matched by the
+    /// ignore list go to `ignored`, updates APT is still phasing in go to
+    /// `phasing` and updates APT keeps back go to `kept_back`, since the regular
+    /// upgrade installs none of them. Called after every check and whenever the
+    /// ignore list changes.
+    fn sort_updates(&mut self) {
+        let all: Vec<Package> = self
+            .packages
+            .drain(..)
+            .chain(self.ignored.drain(..))
+            .chain(self.phasing.drain(..))
+            .chain(self.kept_back.drain(..))
+            .chain(self.vcs.drain(..))
+            .collect();
+
+        for package in all {
+            if !package.hold.is_empty() || self.config.ignore_rule_for(&package).is_some() {
+                self.ignored.push(package);
+            } else if package.phased {
+                self.phasing.push(package);
+            } else if package.kept_back {
+                self.kept_back.push(package);
+            } else if package.is_vcs {
+                self.vcs.push(package);
+            } else {
+                self.packages.push(package);
+            }
+        }
+    }
+
+    /// Look up a pending update by name, including set-aside ones
+    fn find_package(&self, name: &str) -> Option<&Package> {
+        self.packages
+            .iter()
+            .chain(&self.ignored)
+            .chain(&self.phasing)
+            .chain(&self.kept_back)
+            .chain(&self.vcs)
+            .find(|p| p.name == name)
+    }
+
+    /// Number of pending updates whose package files are already in the cache
+    fn downloaded_count(&self) -> usize {
+        // AUR packages are built at install time, so only official ones count
+        self.packages
+            .iter()
+            .filter(|p| !p.is_aur && self.downloaded.contains(&(p.name.clone(), p.new_version.clone())))
+            .count()
+    }
+
+    /// Number of pending updates that could still be pre-downloaded
+    fn pending_download_count(&self) -> usize {
+        self.packages
+            .iter()
+            .filter(|p| !p.is_aur)
+            .count()
+            .saturating_sub(self.downloaded_count())
+    }
+
+    /// Number of pending updates classified as security updates
+    fn security_count(&self) -> usize {
+        self.packages.iter().filter(|p| p.is_security).count()
+    }
+
+    /// Pick the panel/header icon: security shield, alert or normal penguin
+    fn status_icon(&self) -> &'static [u8] {
+        if self.security_count() > 0 {
+            ICON_SECURITY
+        } else if !self.packages.is_empty() || !self.kept_back.is_empty() || self.out_of_sync > 0 {
+            // Kept-back updates and a half-synced pacman database still need the user's attention
+            ICON_ALERT
+        } else {
+            ICON_NORMAL
+        }
+    }
+
+    /// Check whether an update is worth a notification under the configured policy
+    fn is_notable(&self, package: &Package) -> bool {
+        match self.config.notification_policy.as_str() {
+            "major" => package.is_security || package.bump.is_significant(),
+            "security" => package.is_security,
+            _ => true,
+        }
+    }
+
     /// Send a desktop notification about available updates
-    fn send_notification(&self, count: usize) {
+    ///
+    /// Pending security updates always make the notification critical,
+    /// regardless of the configured urgency threshold.
+    fn send_notification(&self, count: usize, security_count: usize) {
         use notify_rust::{Notification, Urgency};
         
-        let urgency = if count >= self.config.urgency_threshold as usize {
+        let urgency = if security_count > 0 || count >= self.config.urgency_threshold as usize {
             Urgency::Critical
         } else {
             Urgency::Normal
         };
         
-        let body = format!("{} update{} available", count, if count == 1 { "" } else { "s" });
+        let mut body = format!("{} update{} available", count, if count == 1 { "" } else { "s" });
+        if security_count > 0 {
+            body.push_str(&format!(" ({} security)", security_count));
+        }
+
+        // Downgrades usually mean an outdated mirror, so call them out
+        let downgrades = self.packages.iter().filter(|p| p.bump == Bump::Downgrade).count();
+        if downgrades > 0 {
+            body.push_str(&format!(
+                "\n⚠ {} downgrade{} offered, check your mirrors",
+                downgrades,
+                if downgrades == 1 { "" } else { "s" }
+            ));
+        }
         
         // Try to send notification, ignore errors (don't crash if notification fails)
         let _ = Notification::new()
@@ -654,6 +2018,843 @@ impl UpdateChecker {
             .show();
     }
 
+    /// Send a desktop notification about new commits in VCS packages
+    fn send_vcs_notification(&self, count: usize) {
+        use notify_rust::Notification;
+
+        let _ = Notification::new()
+            .summary("Updates Applet for COSMIC")
+            .body(&format!(
+                "{} VCS package{} with new commits",
+                count,
+                if count == 1 { "" } else { "s" }
+            ))
+            .icon("cosmic-ext-applet-updates")
+            .show();
+    }
+
+    /// Send a desktop notification about security updates installed without prompting
+    fn send_security_notification(&self, summary: &str) {
+        use notify_rust::Notification;
+
+        let _ = Notification::new()
+            .summary("Updates Applet for COSMIC")
+            .body(summary)
+            .icon("cosmic-ext-applet-updates")
+            .show();
+    }
+
+    /// Render the detail page for a single package
+    fn package_view(&self, name: &str) -> Element<'_, Message> {
+        let Some(package) = self.find_package(name) else {
+            // Package disappeared after a re-check, nothing to show
+            return self
+                .core
+                .applet
+                .popup_container(
+                    widget::column()
+                        .push(widget::text(format!("{} is no longer pending", name)).size(14))
+                        .push(widget::button::standard("Back").on_press(Message::ClosePackage))
+                        .spacing(12)
+                        .padding(16),
+                )
+                .into();
+        };
+
+        use cosmic::iced::widget::text::Span;
+
+        let header = widget::text(&package.name).size(20);
+        let versions = cosmic::iced::widget::rich_text(vec![
+            Span::new(&package.current_version).color(cosmic::iced::Color::from_rgb(0.9, 0.3, 0.3)), // Red
+            Span::new(" → "),
+            Span::new(&package.new_version).color(cosmic::iced::Color::from_rgb(0.3, 0.8, 0.3)), // Green
+        ])
+        .size(13);
+
+        let mut details_column = widget::column()
+            .push(widget::text("Details").size(16))
+            .spacing(4);
+
+        match &self.details {
+            None => {
+                details_column = details_column.push(widget::text("Loading details...").size(12));
+            }
+            Some(Err(e)) => {
+                details_column = details_column.push(widget::text(format!("❌ {}", e)).size(12));
+            }
+            Some(Ok(details)) => {
+                if !details.description.is_empty() {
+                    details_column = details_column.push(widget::text(&details.description).size(12));
+                }
+
+                let fields = [
+                    ("Repository", &details.repository),
+                    ("License", &details.licenses),
+                    ("Packager", &details.packager),
+                    ("Build date", &details.build_date),
+                    ("Download size", &details.download_size),
+                    ("Installed size", &details.installed_size),
+                ];
+                for (label, value) in fields {
+                    if !value.is_empty() {
+                        details_column =
+                            details_column.push(widget::text(format!("{}: {}", label, value)).size(12));
+                    }
+                }
+
+                // AUR metadata from the RPC, filled in during the check (no timestamp if the query failed)
+                if package.is_aur && package.aur_last_modified > 0 {
+                    let maintainer = if package.aur_maintainer.is_empty() {
+                        "nobody (orphaned)".to_string()
+                    } else {
+                        package.aur_maintainer.clone()
+                    };
+                    details_column = details_column.push(
+                        widget::text(format!(
+                            "AUR: {} votes, maintained by {}, last updated {}",
+                            package.aur_votes,
+                            maintainer,
+                            aur_rpc::format_date(package.aur_last_modified)
+                        ))
+                        .size(12),
+                    );
+                    if let Some(since) = package.aur_out_of_date {
+                        details_column = details_column.push(
+                            widget::text(format!("⚑ Flagged out of date since {}", aur_rpc::format_date(since)))
+                                .size(12),
+                        );
+                    }
+                }
+
+                if !details.url.is_empty() {
+                    details_column = details_column.push(
+                        widget::button::link(details.url.clone())
+                            .on_press(Message::OpenUrl(details.url.clone())),
+                    );
+                }
+
+                if !details.added_dependencies.is_empty() {
+                    details_column = details_column.push(
+                        widget::text(format!("➕ New dependencies: {}", details.added_dependencies.join(", ")))
+                            .size(12),
+                    );
+                }
+                if !details.removed_dependencies.is_empty() {
+                    details_column = details_column.push(
+                        widget::text(format!(
+                            "➖ Dropped dependencies: {}",
+                            details.removed_dependencies.join(", ")
+                        ))
+                        .size(12),
+                    );
+                }
+            }
+        }
+
+        let mut changelog_column = widget::column()
+            .push(widget::text("Changelog").size(16))
+            .spacing(8);
+
+        match &self.changelog {
+            None => {
+                changelog_column = changelog_column.push(widget::text("Loading changelog...").size(12));
+            }
+            Some(Err(e)) => {
+                changelog_column = changelog_column.push(widget::text(format!("❌ {}", e)).size(12));
+            }
+            Some(Ok(entries)) if entries.is_empty() => {
+                changelog_column = changelog_column
+                    .push(widget::text("No changelog entries for this version range").size(12));
+            }
+            Some(Ok(entries)) => {
+                for entry in entries {
+                    let title = if entry.date.is_empty() {
+                        entry.version.clone()
+                    } else {
+                        format!("{} ({})", entry.version, entry.date)
+                    };
+                    changelog_column = changelog_column.push(
+                        widget::column()
+                            .push(widget::text(title).size(13))
+                            .push(widget::text(entry.text.trim_end()).size(11))
+                            .spacing(4),
+                    );
+                }
+            }
+        }
+
+        // PKGBUILD review for AUR packages, coloured like `git diff`
+        let mut pkgbuild_column = widget::column()
+            .push(widget::text("PKGBUILD changes").size(16))
+            .spacing(4);
+
+        match &self.pkgbuild_diff {
+            _ if !package.is_aur => {}
+            None => {
+                pkgbuild_column = pkgbuild_column.push(widget::text("Loading PKGBUILD diff...").size(12));
+            }
+            Some(Err(e)) => {
+                pkgbuild_column = pkgbuild_column.push(widget::text(format!("❌ {}", e)).size(12));
+            }
+            Some(Ok(diff)) if diff.trim().is_empty() => {
+                pkgbuild_column = pkgbuild_column
+                    .push(widget::text("No changes to the PKGBUILD or install scripts").size(12));
+            }
+            Some(Ok(diff)) => {
+                let spans: Vec<Span> = diff
+                    .lines()
+                    .map(|line| {
+                        let span = Span::new(format!("{}\n", line)).font(cosmic::font::mono());
+                        if line.starts_with("+++") || line.starts_with("---") {
+                            span
+                        } else if line.starts_with('+') {
+                            span.color(cosmic::iced::Color::from_rgb(0.3, 0.8, 0.3)) // Green
+                        } else if line.starts_with('-') {
+                            span.color(cosmic::iced::Color::from_rgb(0.9, 0.3, 0.3)) // Red
+                        } else if line.starts_with("@@") {
+                            span.color(cosmic::iced::Color::from_rgb(0.4, 0.6, 0.9)) // Blue
+                        } else {
+                            span
+                        }
+                    })
+                    .collect();
+                pkgbuild_column = pkgbuild_column.push(cosmic::iced::widget::rich_text(spans).size(11));
+            }
+        }
+
+        // Ignore action, or the rule that already hides this update
+        let ignore_row: Element<'_, Message> = match self.config.ignore_rule_for(package) {
+            Some(rule) => {
+                let mut text = format!("🚫 Ignored by \"{}\"", rule.pattern);
+                if !rule.reason.is_empty() {
+                    text.push_str(&format!(": {}", rule.reason));
+                }
+                widget::text(text).size(12).into()
+            }
+            None => widget::row()
+                .push(
+                    widget::text_input("Reason (optional)", &self.ignore_reason_input)
+                        .on_input(Message::SetIgnoreReason)
+                        .width(Length::Fill),
+                )
+                .push(
+                    widget::button::standard("Ignore").on_press(Message::IgnorePackage(package.name.clone())),
+                )
+                .spacing(8)
+                .align_y(Alignment::Center)
+                .into(),
+        };
+
+        // Distro-level hold state and action
+        let hold_row = if package.hold.is_empty() {
+            widget::row()
+                .push(widget::text("Not held").size(12))
+                .push(widget::horizontal_space())
+                .push(
+                    widget::button::standard("Hold")
+                        .on_press(Message::SetHold(package.name.clone(), true)),
+                )
+        } else {
+            widget::row()
+                .push(widget::text(format!("📌 Held by {}", package.hold)).size(12))
+                .push(widget::horizontal_space())
+                .push(
+                    widget::button::standard("Unhold")
+                        .on_press(Message::SetHold(package.name.clone(), false)),
+                )
+        }
+        .spacing(8)
+        .align_y(Alignment::Center);
+
+        let buttons = widget::row()
+            .push(widget::button::standard("Back").on_press(Message::ClosePackage))
+            .push(widget::horizontal_space());
+
+        let mut content = widget::column()
+            .push(header)
+            .push(versions)
+            .push(
+                widget::scrollable({
+                    let sections = widget::column().push(details_column);
+                    // Review the build script before the changelog, it is what actually runs
+                    let sections = if package.is_aur { sections.push(pkgbuild_column) } else { sections };
+                    sections.push(changelog_column).spacing(16)
+                })
+                .height(Length::Fixed(400.0)),
+            )
+            .push(hold_row)
+            .push(ignore_row);
+
+        // Failed hold/ignore actions are reported here
+        if let Some(error) = &self.error {
+            content = content.push(widget::text(format!("❌ {}", error)).size(12));
+        }
+
+        let content = content.push(buttons).spacing(12).padding(16);
+
+        self.core.applet.popup_container(content).into()
+    }
+
+    /// Render the "Kept back" section of the main view
+    fn kept_back_view(&self) -> Element<'_, Message> {
+        let mut col = widget::column()
+            .push(
+                widget::row()
+                    .push(widget::text(format!("⏸ Kept back ({})", self.kept_back.len())).size(14))
+                    .push(widget::horizontal_space())
+                    .push(
+                        widget::button::standard("Full Upgrade").on_press(Message::UpgradeKeptBack(None)),
+                    )
+                    .spacing(8)
+                    .align_y(Alignment::Center),
+            )
+            .push(
+                widget::text("apt upgrade does not install updates that need new packages or removals")
+                    .size(11),
+            )
+            .spacing(4);
+
+        for package in &self.kept_back {
+            let mut info = widget::column()
+                .push(
+                    widget::text(format!(
+                        "📦 {}: {} → {}",
+                        package.name, package.current_version, package.new_version
+                    ))
+                    .size(12),
+                )
+                .spacing(2);
+            if !package.kept_back_reason.is_empty() {
+                info = info.push(widget::text(&package.kept_back_reason).size(11));
+            }
+
+            col = col.push(
+                widget::row()
+                    .push(
+                        widget::mouse_area(widget::container(info).padding(6))
+                            .on_press(Message::OpenPackage(package.name.clone())),
+                    )
+                    .push(widget::horizontal_space())
+                    .push(
+                        widget::button::standard("Install")
+                            .on_press(Message::UpgradeKeptBack(Some(package.name.clone()))),
+                    )
+                    .spacing(8)
+                    .align_y(Alignment::Center),
+            );
+        }
+
+        col.into()
+    }
+
+    /// Render the "AUR maintenance" section of the main view
+    fn foreign_issues_view(&self) -> Element<'_, Message> {
+        let mut col = widget::column()
+            .push(widget::text(format!("⚠ AUR maintenance ({})", self.foreign_issues.len())).size(14))
+            .push(widget::text("Installed AUR packages that may stop getting updates").size(11))
+            .spacing(4);
+
+        for package in &self.foreign_issues {
+            col = col.push(
+                widget::container(
+                    widget::text(format!(
+                        "📦 {} {}: {}",
+                        package.name,
+                        package.version,
+                        package.issue.describe()
+                    ))
+                    .size(12),
+                )
+                .padding(6),
+            );
+        }
+
+        col.into()
+    }
+
+    /// Render the banner for a new distribution release or an unsupported one
+    fn release_upgrade_view(&self, upgrade: &ReleaseUpgrade) -> Element<'_, Message> {
+        let title = if upgrade.available.is_empty() {
+            format!("⚠ {} is no longer supported", upgrade.current)
+        } else {
+            format!("🚀 New release available: {}", upgrade.available)
+        };
+
+        let mut col = widget::column()
+            .push(widget::text(title).size(15))
+            .spacing(6);
+
+        if upgrade.end_of_life {
+            let since = if upgrade.eol_date.is_empty() {
+                String::new()
+            } else {
+                format!(" since {}", upgrade.eol_date)
+            };
+            col = col.push(
+                widget::text(format!(
+                    "{} no longer receives security updates{}. Upgrade to a supported release.",
+                    upgrade.current, since
+                ))
+                .size(12),
+            );
+        } else if !upgrade.eol_date.is_empty() {
+            col = col.push(
+                widget::text(format!("{} is supported until {}", upgrade.current, upgrade.eol_date)).size(12),
+            );
+        }
+
+        // Tool-driven upgrades run in a terminal, manual ones follow the guide
+        let upgrade_button = if upgrade.available.is_empty() {
+            None
+        } else if upgrade.command.is_empty() {
+            Some(widget::button::suggested("Upgrade Guide").on_press(Message::StartReleaseUpgrade))
+        } else {
+            Some(widget::button::suggested("Upgrade…").on_press(Message::StartReleaseUpgrade))
+        };
+
+        let mut buttons = widget::row().spacing(8).align_y(Alignment::Center);
+        if !upgrade.release_notes.is_empty() {
+            buttons = buttons.push(
+                widget::button::standard("Release Notes").on_press(Message::OpenUrl(upgrade.release_notes.clone())),
+            );
+        }
+        if !upgrade.command.is_empty() || upgrade.available.is_empty() {
+            buttons = buttons
+                .push(widget::button::standard("How to Upgrade").on_press(Message::OpenUrl(upgrade.guide.clone())));
+        }
+        buttons = buttons.push(widget::horizontal_space());
+        if let Some(button) = upgrade_button {
+            buttons = buttons.push(button);
+        }
+
+        widget::container(col.push(buttons)).padding(8).into()
+    }
+
+    /// Render the "Repositories" section of the main view
+    fn repositories_view(&self) -> Element<'_, Message> {
+        let mut col = widget::column()
+            .push(widget::text(format!("⚠ Repositories ({})", self.repository_issues.len())).size(14))
+            .push(widget::text("Repositories with problems may stop delivering updates").size(11))
+            .spacing(4);
+
+        for issue in &self.repository_issues {
+            let icon = if issue.is_error { "❌" } else { "⚠" };
+            let repository = if issue.repository.is_empty() {
+                "Unknown repository"
+            } else {
+                &issue.repository
+            };
+            col = col.push(
+                widget::container(
+                    widget::column()
+                        .push(widget::text(format!("{} {}: {}", icon, repository, issue.kind.label())).size(12))
+                        .push(widget::text(&issue.message).size(11))
+                        .spacing(2),
+                )
+                .padding(6),
+            );
+        }
+
+        col.into()
+    }
+
+    /// Render the "VCS packages" section of the main view
+    fn vcs_view(&self) -> Element<'_, Message> {
+        let help = if self.config.upgrade.aur_devel {
+            "New upstream commits, rebuilt by the next upgrade"
+        } else {
+            "New upstream commits, enable --devel in the upgrade settings to rebuild them"
+        };
+        let mut col = widget::column()
+            .push(widget::text(format!("🔧 VCS packages ({})", self.vcs.len())).size(14))
+            .push(widget::text(help).size(11))
+            .spacing(4);
+
+        for package in &self.vcs {
+            col = col.push(
+                widget::mouse_area(
+                    widget::container(
+                        widget::text(format!(
+                            "📦 {}: {} → {}",
+                            package.name, package.current_version, package.new_version
+                        ))
+                        .size(12),
+                    )
+                    .padding(6),
+                )
+                .on_press(Message::OpenPackage(package.name.clone())),
+            );
+        }
+
+        col.into()
+    }
+
+    /// Render the "Phasing in" section of the main view
+    fn phasing_view(&self) -> Element<'_, Message> {
+        let mut col = widget::column()
+            .push(widget::text(format!("⏳ Phasing in ({})", self.phasing.len())).size(14))
+            .push(
+                widget::text("Released to a share of systems at a time, apt upgrade will install them once this one is included")
+                    .size(11),
+            )
+            .spacing(4);
+
+        for package in &self.phasing {
+            let rollout = package
+                .phased_percentage
+                .map(|percentage| format!(" ({}% rolled out)", percentage))
+                .unwrap_or_default();
+            col = col.push(
+                widget::mouse_area(
+                    widget::container(
+                        widget::text(format!(
+                            "📦 {}: {} → {}{}",
+                            package.name, package.current_version, package.new_version, rollout
+                        ))
+                        .size(12),
+                    )
+                    .padding(6),
+                )
+                .on_press(Message::OpenPackage(package.name.clone())),
+            );
+        }
+
+        col.into()
+    }
+
+    /// Render the collapsible "Held or ignored" section of the main view
+    fn ignored_view(&self) -> Element<'_, Message> {
+        use cosmic::iced::widget::text::Span;
+
+        let grey = cosmic::iced::Color::from_rgb(0.5, 0.5, 0.5);
+        let arrow = if self.show_ignored { "▾" } else { "▸" };
+
+        let mut col = widget::column()
+            .push(
+                widget::button::text(format!("{} Held or ignored ({})", arrow, self.ignored.len()))
+                    .on_press(Message::ToggleShowIgnored),
+            )
+            .spacing(4);
+
+        if self.show_ignored {
+            for package in &self.ignored {
+                let mut spans = vec![Span::new(format!(
+                    "📦 {}: {} → {}",
+                    package.name, package.current_version, package.new_version
+                ))
+                .color(grey)];
+
+                if !package.hold.is_empty() {
+                    spans.push(Span::new(format!(" 📌 {}", package.hold)).color(grey));
+                } else if let Some(rule) = self.config.ignore_rule_for(package) {
+                    if !rule.reason.is_empty() {
+                        spans.push(Span::new(format!(" ({})", rule.reason)).color(grey));
+                    }
+                }
+
+                col = col.push(
+                    widget::mouse_area(
+                        widget::container(cosmic::iced::widget::rich_text(spans).size(12)).padding(6),
+                    )
+                    .on_press(Message::OpenPackage(package.name.clone())),
+                );
+            }
+        }
+
+        col.into()
+    }
+
+    /// Render the upgrade command options and a preview of the resulting command
+    fn upgrade_strategy_view(&self) -> Element<'_, Message> {
+        let Some(pm) = &self.package_manager else {
+            return widget::column().into();
+        };
+        let strategy = &self.pending_config.upgrade;
+
+        let toggle_row = |label: &'static str, value: bool, message: fn(bool) -> Message| {
+            widget::row()
+                .push(widget::text(label).size(14))
+                .push(widget::horizontal_space())
+                .push(widget::toggler(value).on_toggle(message))
+                .spacing(12)
+                .padding([4, 0])
+                .align_y(Alignment::Center)
+        };
+
+        let mut col = widget::column()
+            .push(widget::text("Upgrade command").size(16))
+            .spacing(4);
+
+        let (noconfirm, extra_flags) = match pm {
+            PackageManager::Apt(_) => {
+                col = col.push(toggle_row(
+                    "Use full-upgrade:",
+                    strategy.apt_full_upgrade,
+                    Message::ToggleAptFullUpgrade,
+                ));
+                (strategy.apt_assume_yes, &strategy.apt_extra_flags)
+            }
+            pm if pm.has_aur_helper() => {
+                col = col
+                    .push(toggle_row(
+                        "Include VCS packages (--devel):",
+                        strategy.aur_devel,
+                        Message::ToggleAurDevel,
+                    ))
+                    .push(toggle_row(
+                        "Skip AUR packages (--repo):",
+                        strategy.aur_skip,
+                        Message::ToggleSkipAur,
+                    ));
+                (strategy.aur_noconfirm, &strategy.aur_extra_flags)
+            }
+            _ => (strategy.pacman_noconfirm, &strategy.pacman_extra_flags),
+        };
+
+        col = col
+            .push(toggle_row(
+                "Skip confirmation prompts:",
+                noconfirm,
+                Message::ToggleUpgradeNoConfirm,
+            ))
+            .push(
+                widget::row()
+                    .push(widget::text("Extra flags:").size(14))
+                    .push(widget::horizontal_space())
+                    .push(
+                        widget::text_input("none", extra_flags)
+                            .on_input(Message::SetUpgradeExtraFlags)
+                            .width(Length::Fixed(200.0)),
+                    )
+                    .spacing(12)
+                    .padding([4, 0])
+                    .align_y(Alignment::Center),
+            );
+
+        // Preview of what the Upgrade button will run
+        let preview = match pm.upgrade_command(strategy) {
+            Ok(command) => format!("$ {}", command),
+            Err(e) => format!("❌ {}", e),
+        };
+        col = col.push(widget::text(preview).size(11));
+
+        col.into()
+    }
+
+    /// Render the ignore list editor
+    fn ignore_list_view(&self) -> Element<'_, Message> {
+        let header = widget::text("Ignored Packages").size(20);
+
+        let mut rules = widget::column().spacing(8);
+        if self.pending_config.ignored_packages.is_empty() {
+            rules = rules.push(widget::text("No packages are ignored").size(12));
+        }
+        for (index, rule) in self.pending_config.ignored_packages.iter().enumerate() {
+            let mut description = rule.pattern.clone();
+            if !rule.scope.is_empty() {
+                description.push_str(&format!(" [{}]", rule.scope));
+            }
+            if !rule.reason.is_empty() {
+                description.push_str(&format!(" — {}", rule.reason));
+            }
+
+            rules = rules.push(
+                widget::row()
+                    .push(widget::text(description).size(13))
+                    .push(widget::horizontal_space())
+                    .push(widget::button::standard("Remove").on_press(Message::RemoveIgnoreRule(index)))
+                    .spacing(8)
+                    .align_y(Alignment::Center),
+            );
+        }
+
+        // Form for a new rule
+        let pattern_row = widget::row()
+            .push(widget::text("Pattern:").size(14))
+            .push(widget::horizontal_space())
+            .push(
+                widget::text_input("linux-*", &self.ignore_pattern_input)
+                    .on_input(Message::SetIgnorePattern)
+                    .width(Length::Fixed(200.0)),
+            )
+            .spacing(12)
+            .align_y(Alignment::Center);
+
+        let scope_row = widget::row()
+            .push(widget::text("Source:").size(14))
+            .push(widget::horizontal_space())
+            .push(
+                widget::text_input("any", &self.ignore_scope_input)
+                    .on_input(Message::SetIgnoreScope)
+                    .width(Length::Fixed(200.0)),
+            )
+            .spacing(12)
+            .align_y(Alignment::Center);
+
+        let scope_help = widget::text("(empty for any, official, aur or a repository name)").size(11);
+
+        let reason_row = widget::row()
+            .push(widget::text("Reason:").size(14))
+            .push(widget::horizontal_space())
+            .push(
+                widget::text_input("Optional", &self.ignore_reason_input)
+                    .on_input(Message::SetIgnoreReason)
+                    .width(Length::Fixed(200.0)),
+            )
+            .spacing(12)
+            .align_y(Alignment::Center);
+
+        let add_row = widget::row()
+            .push(widget::horizontal_space())
+            .push(if self.ignore_pattern_input.trim().is_empty() {
+                widget::button::standard("Add")
+            } else {
+                widget::button::standard("Add").on_press(Message::AddIgnoreRule)
+            });
+
+        let buttons = widget::row()
+            .push(widget::button::standard("Back").on_press(Message::CloseIgnoreList))
+            .push(widget::horizontal_space());
+
+        let content = widget::column()
+            .push(header)
+            .push(widget::scrollable(rules).height(Length::Fixed(200.0)))
+            .push(pattern_row)
+            .push(scope_row)
+            .push(scope_help)
+            .push(reason_row)
+            .push(add_row)
+            .push(widget::text("Changes are applied when you save the settings").size(11))
+            .push(buttons)
+            .spacing(12)
+            .padding(16);
+
+        self.core.applet.popup_container(content).into()
+    }
+
+    /// Render the package source inventory
+    fn sources_view(&self) -> Element<'_, Message> {
+        let header = widget::text("Package Sources").size(20);
+
+        let mut list = widget::column().spacing(8);
+        match &self.sources {
+            None => list = list.push(widget::text("Reading sources...").size(12)),
+            Some(sources) if sources.is_empty() => {
+                list = list.push(widget::text("No package sources found").size(12));
+            }
+            Some(sources) => {
+                for (index, source) in sources.iter().enumerate() {
+                    let icon = if source.official { "✅" } else { "🌐" };
+                    let mut badges = vec![
+                        source.kind.label(),
+                        if source.official { "official" } else { "third-party" },
+                    ];
+                    if !source.enabled {
+                        badges.push("disabled");
+                    }
+
+                    let mut info = widget::column()
+                        .push(widget::text(format!("{} {}", icon, source.name)).size(13))
+                        .push(widget::text(badges.join(" · ")).size(11))
+                        .spacing(2);
+                    if !source.detail.is_empty() {
+                        info = info.push(widget::text(&source.detail).size(11));
+                    }
+                    if !source.file.as_os_str().is_empty() {
+                        info = info.push(widget::text(source.file.display().to_string()).size(11));
+                    }
+                    if source.disabled_by_upgrade {
+                        info = info.push(
+                            widget::text("⚠ Disabled by a release upgrade, check it supports this release first")
+                                .size(11),
+                        );
+                    }
+
+                    let toggle = if source.enabled {
+                        widget::button::standard("Disable").on_press(Message::SetSourceEnabled(index, false))
+                    } else {
+                        widget::button::standard("Enable").on_press(Message::SetSourceEnabled(index, true))
+                    };
+
+                    list = list.push(
+                        widget::row()
+                            .push(info)
+                            .push(widget::horizontal_space())
+                            .push(toggle)
+                            .spacing(8)
+                            .align_y(Alignment::Center),
+                    );
+                }
+            }
+        }
+
+        let third_party = self
+            .sources
+            .iter()
+            .flatten()
+            .filter(|source| source.enabled && !source.official)
+            .count();
+        let summary = format!(
+            "{} enabled third-party source{} can deliver updates",
+            third_party,
+            if third_party == 1 { "" } else { "s" }
+        );
+
+        let mut content = widget::column()
+            .push(header)
+            .push(widget::text(summary).size(12))
+            .push(widget::scrollable(list).height(Length::Fixed(360.0)));
+
+        if let Some(error) = &self.error {
+            content = content.push(widget::text(format!("❌ {}", error)).size(13));
+        }
+
+        let buttons = widget::row()
+            .push(widget::button::standard("Back").on_press(Message::CloseSources))
+            .push(widget::horizontal_space());
+
+        let content = content.push(buttons).spacing(12).padding(16);
+
+        self.core.applet.popup_container(content).into()
+    }
+
+    /// Render the Arch news list shown before an upgrade
+    fn news_view(&self) -> Element<'_, Message> {
+        let mut col = widget::column()
+            .push(widget::text("📰 Arch Linux news since your last upgrade").size(14))
+            .spacing(6);
+
+        for item in &self.news {
+            let marker = if item.requires_manual_intervention() { "⚠" } else { "•" };
+            col = col.push(
+                widget::row()
+                    .push(widget::text(format!("{} {}", marker, item.title)).size(12))
+                    .push(widget::horizontal_space())
+                    .push(widget::button::standard("Read").on_press(Message::OpenUrl(item.link.clone())))
+                    .spacing(8)
+                    .align_y(Alignment::Center),
+            );
+        }
+
+        let actions = if self.news_needs_ack {
+            col = col.push(
+                widget::text("Manual intervention required: read the news above before upgrading.")
+                    .size(12),
+            );
+            widget::row()
+                .push(widget::button::standard("Cancel").on_press(Message::DismissNews))
+                .push(widget::horizontal_space())
+                .push(widget::button::suggested("I have read it, upgrade").on_press(Message::AcknowledgeNews))
+                .spacing(8)
+        } else {
+            widget::row()
+                .push(widget::horizontal_space())
+                .push(widget::button::standard("Dismiss").on_press(Message::DismissNews))
+        };
+
+        widget::container(col.push(actions)).padding(6).into()
+    }
+
     /// Render the settings view
     fn settings_view(&self) -> Element<'_, Message> {
         let header = widget::text("Settings").size(20);
@@ -715,6 +2916,31 @@ impl UpdateChecker {
             .padding([8, 0])
             .align_y(Alignment::Center);
 
+        // Notification policy dropdown
+        let policy_label = widget::text("Notify for:").size(14);
+        let policy_index = match self.pending_config.notification_policy.as_str() {
+            "major" => 1,
+            "security" => 2,
+            _ => 0,
+        };
+        let policy_dropdown =
+            widget::dropdown(&self.notification_policy_options, Some(policy_index), |index| {
+                let policy = match index {
+                    1 => "major",
+                    2 => "security",
+                    _ => "all",
+                };
+                Message::SetNotificationPolicy(policy.to_string())
+            });
+
+        let policy_row = widget::row()
+            .push(policy_label)
+            .push(widget::horizontal_space())
+            .push(policy_dropdown)
+            .spacing(12)
+            .padding([8, 0])
+            .align_y(Alignment::Center);
+
         // Urgency threshold input
         let threshold_label = widget::text("Urgency threshold:").size(14);
         let threshold_input = widget::text_input(
@@ -764,6 +2990,158 @@ impl UpdateChecker {
             .push(terminal_help)
             .padding([8, 0]);
 
+        // Background download toggle
+        let predownload_label = widget::text("Download updates in the background:").size(14);
+        let predownload_toggle = widget::toggler(self.pending_config.predownload_updates)
+            .on_toggle(Message::TogglePredownload);
+
+        let predownload_row = widget::row()
+            .push(predownload_label)
+            .push(widget::horizontal_space())
+            .push(predownload_toggle)
+            .spacing(12)
+            .padding([8, 0])
+            .align_y(Alignment::Center);
+
+        // Unattended security updates toggle
+        let unattended_label = widget::text("Install security updates automatically:").size(14);
+        let unattended_toggle = widget::toggler(self.pending_config.unattended_security_updates)
+            .on_toggle(Message::ToggleUnattendedSecurity);
+
+        let unattended_help = widget::text(format!(
+            "(between {:02}:00 and {:02}:00)",
+            self.pending_config.maintenance_window_start, self.pending_config.maintenance_window_end
+        ))
+        .size(11);
+
+        let unattended_row = widget::row()
+            .push(unattended_label)
+            .push(widget::horizontal_space())
+            .push(unattended_toggle)
+            .spacing(12)
+            .align_y(Alignment::Center);
+
+        // Only offered where the backend can upgrade security updates on their own (APT)
+        let unattended_supported = self.package_manager.as_ref().is_some_and(unattended::is_supported);
+        let unattended_section = if unattended_supported {
+            widget::column()
+                .push(unattended_row)
+                .push(widget::vertical_space().height(Length::Fixed(4.0)))
+                .push(unattended_help)
+                .padding([8, 0])
+        } else {
+            widget::column()
+        };
+
+        // Metered connection toggle
+        let metered_label = widget::text("Skip scheduled checks on metered networks:").size(14);
+        let metered_toggle = widget::toggler(self.pending_config.skip_checks_on_metered)
+            .on_toggle(Message::ToggleSkipChecksOnMetered);
+
+        let metered_row = widget::row()
+            .push(metered_label)
+            .push(widget::horizontal_space())
+            .push(metered_toggle)
+            .spacing(12)
+            .padding([8, 0])
+            .align_y(Alignment::Center);
+
+        // Minimum battery input
+        let battery_label = widget::text("Minimum battery to upgrade:").size(14);
+        let battery_input = widget::text_input("Percent", &self.battery_input_value)
+            .on_input(Message::SetMinBatteryPercent)
+            .width(Length::Fixed(100.0));
+
+        let battery_help = widget::text("(%, 0 = off)").size(11);
+
+        let battery_row = widget::row()
+            .push(battery_label)
+            .push(widget::horizontal_space())
+            .push(battery_input)
+            .push(battery_help)
+            .spacing(12)
+            .padding([8, 0])
+            .align_y(Alignment::Center);
+
+        // AUR helper choice, only offered on Arch
+        let is_arch = self.package_manager.as_ref().is_some_and(|pm| pm.is_arch());
+        let helper_row = if is_arch {
+            let helper_index = self
+                .aur_helper_options
+                .iter()
+                .position(|option| *option == self.pending_config.aur_helper);
+            let options = self.aur_helper_options.clone();
+            widget::row()
+                .push(widget::text("AUR helper:").size(14))
+                .push(widget::horizontal_space())
+                .push(widget::dropdown(&self.aur_helper_options, helper_index, move |index| {
+                    Message::SetAurHelper(options[index].clone())
+                }))
+                .spacing(12)
+                .padding([8, 0])
+                .align_y(Alignment::Center)
+        } else {
+            widget::row()
+        };
+
+        // VCS package check, only offered with an AUR helper
+        let has_aur_helper = self.package_manager.as_ref().is_some_and(|pm| pm.has_aur_helper());
+        let vcs_section = if has_aur_helper {
+            widget::column()
+                .push(
+                    widget::row()
+                        .push(widget::text("Check VCS packages for new commits:").size(14))
+                        .push(widget::horizontal_space())
+                        .push(
+                            widget::toggler(self.pending_config.check_vcs_packages)
+                                .on_toggle(Message::ToggleCheckVcs),
+                        )
+                        .spacing(12)
+                        .align_y(Alignment::Center),
+                )
+                .push(
+                    widget::row()
+                        .push(widget::text("Notify about VCS packages:").size(14))
+                        .push(widget::horizontal_space())
+                        .push(
+                            widget::toggler(self.pending_config.notify_vcs_packages)
+                                .on_toggle(Message::ToggleNotifyVcs),
+                        )
+                        .spacing(12)
+                        .align_y(Alignment::Center),
+                )
+                .spacing(8)
+                .padding([8, 0])
+        } else {
+            widget::column()
+        };
+
+        // Upgrade command options for the detected backend
+        let upgrade_section = self.upgrade_strategy_view();
+
+        // Ignore list summary, edited on its own page
+        let ignore_count = self.pending_config.ignored_packages.len();
+        let ignore_row = widget::row()
+            .push(widget::text("Ignored packages:").size(14))
+            .push(widget::horizontal_space())
+            .push(
+                widget::text(format!("{} rule{}", ignore_count, if ignore_count == 1 { "" } else { "s" }))
+                    .size(12),
+            )
+            .push(widget::button::standard("Edit").on_press(Message::OpenIgnoreList))
+            .spacing(12)
+            .padding([8, 0])
+            .align_y(Alignment::Center);
+
+        // Source inventory, reviewed on its own page
+        let sources_row = widget::row()
+            .push(widget::text("Package sources:").size(14))
+            .push(widget::horizontal_space())
+            .push(widget::button::standard("Review").on_press(Message::OpenSources))
+            .spacing(12)
+            .padding([8, 0])
+            .align_y(Alignment::Center);
+
         // Buttons
         let buttons = widget::row()
             .push(widget::button::standard("Back").on_press(Message::CloseSettings))
@@ -771,16 +3149,39 @@ impl UpdateChecker {
             .push(widget::button::suggested("Save").on_press(Message::SaveSettings))
             .spacing(12);
 
-        let mut settings_column = widget::column()
-            .push(header)
-            .push(widget::vertical_space().height(Length::Fixed(20.0)))
+        // Setting rows scroll so the popup stays within its size limits
+        let settings_fields = widget::column()
             .push(interval_row)
             .push(widget::vertical_space().height(Length::Fixed(12.0)))
             .push(notifications_row)
             .push(widget::vertical_space().height(Length::Fixed(12.0)))
+            .push(policy_row)
+            .push(widget::vertical_space().height(Length::Fixed(12.0)))
             .push(threshold_row)
             .push(widget::vertical_space().height(Length::Fixed(12.0)))
-            .push(terminal_section);
+            .push(terminal_section)
+            .push(widget::vertical_space().height(Length::Fixed(12.0)))
+            .push(metered_row)
+            .push(widget::vertical_space().height(Length::Fixed(12.0)))
+            .push(battery_row)
+            .push(widget::vertical_space().height(Length::Fixed(12.0)))
+            .push(predownload_row)
+            .push(widget::vertical_space().height(Length::Fixed(12.0)))
+            .push(unattended_section)
+            .push(widget::vertical_space().height(Length::Fixed(12.0)))
+            .push(helper_row)
+            .push(vcs_section)
+            .push(widget::vertical_space().height(Length::Fixed(12.0)))
+            .push(upgrade_section)
+            .push(widget::vertical_space().height(Length::Fixed(12.0)))
+            .push(ignore_row)
+            .push(widget::vertical_space().height(Length::Fixed(12.0)))
+            .push(sources_row);
+
+        let mut settings_column = widget::column()
+            .push(header)
+            .push(widget::vertical_space().height(Length::Fixed(20.0)))
+            .push(widget::scrollable(settings_fields).height(Length::Fixed(360.0)));
 
         // Show error message if present
         if let Some(error) = &self.error {
@@ -800,3 +3201,15 @@ impl UpdateChecker {
         content.into()
     }
 }
+
+/// Badge color for a version bump: the bigger the jump, the warmer the color
+fn bump_color(bump: Bump) -> cosmic::iced::Color {
+    match bump {
+        Bump::Downgrade => cosmic::iced::Color::from_rgb(0.9, 0.3, 0.3), // Red
+        Bump::Epoch => cosmic::iced::Color::from_rgb(0.7, 0.4, 0.9),     // Purple
+        Bump::Major => cosmic::iced::Color::from_rgb(1.0, 0.6, 0.2),     // Orange
+        Bump::Minor => cosmic::iced::Color::from_rgb(0.9, 0.8, 0.3),     // Yellow
+        Bump::Patch => cosmic::iced::Color::from_rgb(0.4, 0.6, 1.0),     // Blue
+        Bump::Revision | Bump::Unknown => cosmic::iced::Color::from_rgb(0.6, 0.6, 0.6), // Grey
+    }
+}
@@ -0,0 +1,143 @@
+use crate::utils;
+use chrono::{DateTime, Local, NaiveDateTime};
+use std::fs;
+use tokio::task;
+
+/// Default Arch Linux news feed
+pub const DEFAULT_NEWS_URL: &str = "https://archlinux.org/feeds/news/";
+
+/// Pacman log used to find the time of the last full system upgrade
+const PACMAN_LOG: &str = "/var/log/pacman.log";
+
+/// Marker pacman writes at the start of every `-Syu`/`-Su`
+const UPGRADE_MARKER: &str = "starting full system upgrade";
+
+/// A single entry from the Arch news feed
+#[derive(Debug, Clone)]
+pub struct NewsItem {
+    pub title: String,
+    pub link: String,
+    /// Unique identifier (the feed's guid, or the link if missing)
+    pub guid: String,
+    /// Publication time as Unix seconds (0 if the date could not be parsed)
+    pub published: i64,
+}
+
+impl NewsItem {
+    /// Arch marks news that must be read before upgrading with this phrase
+    pub fn requires_manual_intervention(&self) -> bool {
+        self.title.to_lowercase().contains("manual intervention")
+    }
+}
+
+/// Fetch news items published since the last full system upgrade
+///
+/// The time of the last upgrade is taken from pacman's log, falling back to
+/// `fallback_since` (the applet's own record) when the log is unreadable.
+///
+/// # Arguments
+/// * `url` - Feed URL, `file://` URL or local path
+/// * `fallback_since` - Unix seconds to use when pacman.log has no upgrade entry
+pub async fn fetch_since_last_upgrade(url: String, fallback_since: i64) -> Result<Vec<NewsItem>, String> {
+    task::spawn_blocking(move || {
+        let feed = utils::fetch_url(&url)?;
+        let since = last_upgrade_time().unwrap_or(fallback_since);
+
+        Ok(parse_feed(&feed)
+            .into_iter()
+            .filter(|item| item.published > since)
+            .collect())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Find the time of the last full system upgrade in pacman's log
+///
+/// Log lines look like:
+/// "[2024-01-15T10:23:45+0100] [PACMAN] starting full system upgrade"
+fn last_upgrade_time() -> Option<i64> {
+    let log = fs::read_to_string(PACMAN_LOG).ok()?;
+    let line = log.lines().rev().find(|line| line.contains(UPGRADE_MARKER))?;
+    let stamp = line.strip_prefix('[')?.split(']').next()?;
+
+    DateTime::parse_from_str(stamp, "%Y-%m-%dT%H:%M:%S%z")
+        .map(|dt| dt.timestamp())
+        .ok()
+        .or_else(|| {
+            // Old pacman versions logged local time without an offset: "[2019-03-01 12:00]"
+            NaiveDateTime::parse_from_str(stamp, "%Y-%m-%d %H:%M")
+                .ok()?
+                .and_local_timezone(Local)
+                .earliest()
+                .map(|dt| dt.timestamp())
+        })
+}
+
+/// Parse the items of an RSS 2.0 feed
+///
+/// Only the handful of fields the applet needs are extracted, so a small tag
+/// scanner is enough and avoids pulling in an XML parser.
+fn parse_feed(feed: &str) -> Vec<NewsItem> {
+    let mut items = Vec::new();
+    let mut rest = feed;
+
+    while let Some(start) = rest.find("<item>") {
+        let after = &rest[start..];
+        let Some(end) = after.find("</item>") else {
+            break;
+        };
+        let block = &after[..end];
+        rest = &after[end + "</item>".len()..];
+
+        let title = tag_text(block, "title").unwrap_or_default();
+        let link = tag_text(block, "link").unwrap_or_default();
+        if title.is_empty() {
+            continue;
+        }
+
+        let guid = tag_text(block, "guid").unwrap_or_else(|| link.clone());
+        let published = tag_text(block, "pubDate")
+            .and_then(|date| DateTime::parse_from_rfc2822(&date).ok())
+            .map(|dt| dt.timestamp())
+            .unwrap_or(0);
+
+        items.push(NewsItem {
+            title,
+            link,
+            guid,
+            published,
+        });
+    }
+
+    items
+}
+
+/// Extract and decode the text content of the first `<tag>` in a block
+fn tag_text(block: &str, tag: &str) -> Option<String> {
+    let open = format!("<{}", tag);
+    let close = format!("</{}>", tag);
+
+    let start = block.find(&open)?;
+    let content_start = start + block[start..].find('>')? + 1;
+    let content_end = content_start + block[content_start..].find(&close)?;
+    let raw = block[content_start..content_end].trim();
+
+    let text = raw
+        .strip_prefix("<![CDATA[")
+        .and_then(|inner| inner.strip_suffix("]]>"))
+        .map(|inner| inner.to_string())
+        .unwrap_or_else(|| decode_entities(raw));
+
+    Some(text.trim().to_string())
+}
+
+/// Decode the XML entities that appear in feed titles and links
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
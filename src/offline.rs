@@ -0,0 +1,116 @@
+use std::fs;
+use std::path::Path;
+use std::process::Command as StdCommand;
+use tokio::task;
+
+/// Privileged helper script that stages and applies offline updates
+const HELPER_PATH: &str = "/usr/libexec/cosmic-ext-applet-updates/offline-update";
+
+/// Directory the helper stages updates into (target of the `/system-update` symlink)
+const STAGE_DIR: &str = "/var/lib/cosmic-ext-applet-updates/offline";
+
+/// systemd offline-update trigger, see systemd.offline-updates(7)
+const TRIGGER_PATH: &str = "/system-update";
+
+/// Result written by the helper after the offline update ran
+const RESULT_PATH: &str = "/var/lib/cosmic-ext-applet-updates/offline-result";
+
+/// Outcome of the last offline update, as recorded by the helper
+#[derive(Debug, Clone)]
+pub struct OfflineResult {
+    /// Whether the backend's upgrade completed successfully
+    pub success: bool,
+    /// Unix timestamp of when the update finished
+    pub finished: u64,
+    /// Number of packages that were staged
+    pub packages: usize,
+    /// Human readable summary from the helper
+    pub message: String,
+}
+
+/// Stage the pending updates and request an offline update on next boot
+///
+/// The helper, run through pkexec, works out the updates itself and records
+/// the list of packages it will install, so nothing the applet writes is
+/// copied by root. It downloads everything up front, because the offline run
+/// happens before networking is available.
+///
+/// # Arguments
+/// * `backend` - Backend the helper should use ("apt" or "pacman")
+pub async fn stage(backend: &'static str) -> Result<(), String> {
+    task::spawn_blocking(move || {
+        let output = StdCommand::new("pkexec")
+            .args([HELPER_PATH, "stage", backend])
+            .output()
+            .map_err(|e| format!("Failed to run offline update helper: {}", e))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("Staging offline update failed: {}", stderr));
+        }
+
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Remove a pending offline update request
+pub async fn cancel() -> Result<(), String> {
+    task::spawn_blocking(|| {
+        let output = StdCommand::new("pkexec")
+            .args([HELPER_PATH, "cancel"])
+            .output()
+            .map_err(|e| format!("Failed to run offline update helper: {}", e))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("Cancelling offline update failed: {}", stderr));
+        }
+
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Check whether an offline update staged by this applet is pending
+pub fn is_staged() -> bool {
+    fs::read_link(TRIGGER_PATH)
+        .map(|target| target == Path::new(STAGE_DIR))
+        .unwrap_or(false)
+}
+
+/// Read the result of the last offline update, if one has run
+pub fn read_result() -> Option<OfflineResult> {
+    let contents = fs::read_to_string(RESULT_PATH).ok()?;
+    parse_result(&contents)
+}
+
+/// Parse the helper's key=value result file
+fn parse_result(contents: &str) -> Option<OfflineResult> {
+    let mut success = None;
+    let mut finished = None;
+    let mut packages = 0;
+    let mut message = String::new();
+
+    for line in contents.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        match key.trim() {
+            "status" => success = Some(value.trim() == "success"),
+            "finished" => finished = value.trim().parse::<u64>().ok(),
+            "packages" => packages = value.trim().parse::<usize>().unwrap_or(0),
+            "message" => message = value.trim().to_string(),
+            _ => {}
+        }
+    }
+
+    Some(OfflineResult {
+        success: success?,
+        finished: finished?,
+        packages,
+        message,
+    })
+}
@@ -0,0 +1,223 @@
+use flate2::read::GzDecoder;
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::process::Command as StdCommand;
+
+/// Size of a tar header and of the blocks entry data is padded to
+const TAR_BLOCK: usize = 512;
+
+/// One package entry of a pacman database (`desc` and, in old databases, `depends`)
+#[derive(Debug, Clone, Default)]
+pub struct DbPackage {
+    pub name: String,
+    pub version: String,
+    /// Package base, shared by split packages
+    pub base: String,
+    pub description: String,
+    pub url: String,
+    pub licenses: Vec<String>,
+    pub packager: String,
+    /// Build time (Unix seconds), 0 if unknown
+    pub build_date: i64,
+    /// Size of the package file (`%CSIZE%`), only known in sync databases
+    pub download_size: u64,
+    /// Size on disk once installed (`%ISIZE%` in sync, `%SIZE%` in the local database)
+    pub installed_size: u64,
+    /// Dependencies with their version constraints, e.g. "glibc>=2.38"
+    pub depends: Vec<String>,
+    /// Sync repository the entry was read from, empty for the local database
+    pub repository: String,
+}
+
+/// Read the local database of installed packages
+///
+/// # Arguments
+/// * `db_path` - pacman's database directory, e.g. "/var/lib/pacman"
+///
+/// # Returns
+/// * `Ok(HashMap)` - Installed packages by name
+/// * `Err(String)` - The `local` directory could not be read
+pub fn read_local(db_path: &Path) -> Result<HashMap<String, DbPackage>, String> {
+    let local = db_path.join("local");
+    let entries = fs::read_dir(&local).map_err(|e| format!("Failed to read {}: {}", local.display(), e))?;
+
+    let mut packages = HashMap::new();
+    for entry in entries.flatten() {
+        if let Some(package) = read_local_entry(&entry.path()) {
+            packages.insert(package.name.clone(), package);
+        }
+    }
+
+    Ok(packages)
+}
+
+/// Read a single package directory of the local database, e.g. `local/zlib-1:1.3.1-2`
+pub fn read_local_entry(dir: &Path) -> Option<DbPackage> {
+    let mut package = DbPackage::default();
+    parse_entry(&mut package, &fs::read_to_string(dir.join("desc")).ok()?);
+    // Databases written before pacman 4.2 keep the dependencies in a separate file
+    if let Ok(depends) = fs::read_to_string(dir.join("depends")) {
+        parse_entry(&mut package, &depends);
+    }
+
+    (!package.name.is_empty()).then_some(package)
+}
+
+/// Read a sync database archive, e.g. `sync/core.db`
+///
+/// Databases are tar archives, gzip-compressed for the official repositories.
+/// Other compressions (zstd, xz, ...) that custom repositories may use are
+/// converted to a plain tar stream with bsdtar, which pacman depends on.
+///
+/// # Arguments
+/// * `db_file` - Path of the `.db` file
+/// * `repository` - Repository name recorded in each entry
+pub fn read_sync(db_file: &Path, repository: &str) -> Result<Vec<DbPackage>, String> {
+    let raw = fs::read(db_file).map_err(|e| format!("Failed to read {}: {}", db_file.display(), e))?;
+
+    let archive = if raw.starts_with(&[0x1f, 0x8b]) {
+        let mut archive = Vec::new();
+        GzDecoder::new(raw.as_slice())
+            .read_to_end(&mut archive)
+            .map_err(|e| format!("Failed to decompress {}: {}", db_file.display(), e))?;
+        archive
+    } else if is_tar(&raw) {
+        raw
+    } else {
+        let output = StdCommand::new("bsdtar")
+            .args(["-cf", "-", "--format", "ustar"])
+            .arg(format!("@{}", db_file.display()))
+            .output()
+            .map_err(|e| format!("Failed to run bsdtar: {}", e))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("Failed to unpack {}: {}", db_file.display(), stderr));
+        }
+        output.stdout
+    };
+
+    // Entries are "<name>-<version>/desc", grouped by their directory
+    let mut entries: HashMap<String, DbPackage> = HashMap::new();
+    for (path, data) in tar_files(&archive) {
+        let Some((dir, file)) = path.trim_start_matches("./").split_once('/') else {
+            continue;
+        };
+        if file == "desc" || file == "depends" {
+            let package = entries.entry(dir.to_string()).or_default();
+            parse_entry(package, &String::from_utf8_lossy(data));
+        }
+    }
+
+    Ok(entries
+        .into_values()
+        .filter(|package| !package.name.is_empty())
+        .map(|package| DbPackage {
+            repository: repository.to_string(),
+            ..package
+        })
+        .collect())
+}
+
+/// Fill in a package from the `%FIELD%` blocks of a `desc` or `depends` file
+///
+/// Each block is a `%FIELD%` line followed by one value per line and ends at a blank line.
+fn parse_entry(package: &mut DbPackage, contents: &str) {
+    let mut field = "";
+
+    for line in contents.lines() {
+        if line.len() > 2 && line.starts_with('%') && line.ends_with('%') {
+            field = &line[1..line.len() - 1];
+            continue;
+        }
+        if line.is_empty() {
+            continue;
+        }
+
+        match field {
+            "NAME" => package.name = line.to_string(),
+            "VERSION" => package.version = line.to_string(),
+            "BASE" => package.base = line.to_string(),
+            "DESC" => package.description = line.to_string(),
+            "URL" => package.url = line.to_string(),
+            "PACKAGER" => package.packager = line.to_string(),
+            "LICENSE" => package.licenses.push(line.to_string()),
+            "BUILDDATE" => package.build_date = line.parse().unwrap_or(0),
+            "CSIZE" => package.download_size = line.parse().unwrap_or(0),
+            "ISIZE" | "SIZE" => package.installed_size = line.parse().unwrap_or(0),
+            "DEPENDS" => package.depends.push(line.to_string()),
+            _ => {}
+        }
+    }
+}
+
+/// Check for the "ustar" magic of a POSIX tar header
+fn is_tar(data: &[u8]) -> bool {
+    data.get(257..262) == Some(b"ustar".as_slice())
+}
+
+/// List the regular files of an uncompressed tar archive as (path, contents)
+///
+/// Understands ustar name prefixes plus GNU long names and pax `path` records,
+/// which bsdtar writes for paths longer than 100 bytes.
+fn tar_files(archive: &[u8]) -> Vec<(String, &[u8])> {
+    let mut files = Vec::new();
+    let mut long_name: Option<String> = None;
+    let mut offset = 0;
+
+    while offset + TAR_BLOCK <= archive.len() {
+        let header = &archive[offset..offset + TAR_BLOCK];
+        if header.iter().all(|b| *b == 0) {
+            break; // End of archive
+        }
+
+        let size = octal(&header[124..136]);
+        let data_start = offset + TAR_BLOCK;
+        let data_end = (data_start + size).min(archive.len());
+        let data = &archive[data_start..data_end];
+        offset = data_start + size.div_ceil(TAR_BLOCK) * TAR_BLOCK;
+
+        match header[156] {
+            // GNU long name for the next entry
+            b'L' => long_name = Some(c_string(data)),
+            // pax extended header, records look like "<length> path=<value>\n"
+            b'x' => {
+                long_name = String::from_utf8_lossy(data)
+                    .lines()
+                    .find_map(|record| record.split_once(" path=").map(|(_, path)| path.to_string()))
+                    .or(long_name);
+            }
+            b'0' | 0 => {
+                let path = long_name.take().unwrap_or_else(|| {
+                    let name = c_string(&header[0..100]);
+                    let prefix = c_string(&header[345..500]);
+                    if is_tar(header) && !prefix.is_empty() {
+                        format!("{}/{}", prefix, name)
+                    } else {
+                        name
+                    }
+                });
+                files.push((path, data));
+            }
+            _ => long_name = None,
+        }
+    }
+
+    files
+}
+
+/// Read a NUL-terminated header field
+fn c_string(field: &[u8]) -> String {
+    let end = field.iter().position(|b| *b == 0).unwrap_or(field.len());
+    String::from_utf8_lossy(&field[..end]).to_string()
+}
+
+/// Read an octal number header field (padded with NULs or spaces)
+fn octal(field: &[u8]) -> usize {
+    field
+        .iter()
+        .skip_while(|b| **b == b' ')
+        .take_while(|b| (b'0'..=b'7').contains(*b))
+        .fold(0, |value, digit| value * 8 + (digit - b'0') as usize)
+}
@@ -1,7 +1,18 @@
+use super::apt_db;
+use super::changelog::{self, ChangelogEntry};
+use super::details::{self, PackageDetails};
+use super::repo_health::{self, RepositoryIssue};
+use super::strategy::UpgradeStrategy;
 use super::Package;
+use crate::utils;
+use std::fs;
+use std::path::Path;
 use std::process::Command as StdCommand;
 use tokio::task;
 
+/// Directory holding the downloaded APT indices and Release files
+const APT_LISTS_DIR: &str = "/var/lib/apt/lists";
+
 #[derive(Clone)]
 pub struct AptPackageManager;
 
@@ -12,17 +23,36 @@ impl AptPackageManager {
                 return Ok(get_debug_packages());
             }
 
-            let output = StdCommand::new("apt")
-                .args(["list", "--upgradable"])
-                .output()
-                .map_err(|e| format!("Failed to run apt: {}", e))?;
+            let candidates = apt_db::upgradable(Path::new(APT_LISTS_DIR))?;
 
-            if !output.status.success() {
-                return Err("apt command failed".to_string());
-            }
-
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            let packages = parse_apt_output(&stdout);
+            // `apt upgrade` skips updates that are still being phased in, and keeps
+            // back those that need new packages or removals
+            let simulation = simulate_upgrade();
+            let packages = candidates
+                .into_iter()
+                .map(|candidate| {
+                    let mut package = Package {
+                        name: candidate.name,
+                        current_version: candidate.installed_version,
+                        new_version: candidate.version,
+                        is_aur: false,
+                        is_security: candidate.is_security,
+                        repository: candidate.suites.join(","),
+                        origin: candidate.origin,
+                        download_size: candidate.download_size,
+                        installed_size: candidate.installed_size,
+                        ..Default::default()
+                    };
+                    if simulation.deferred.contains(&package.name) {
+                        package.phased = true;
+                        package.phased_percentage = candidate.phased_percentage;
+                    } else if simulation.kept_back.contains(&package.name) {
+                        package.kept_back = true;
+                        package.kept_back_reason = kept_back_reason(&package.name);
+                    }
+                    package
+                })
+                .collect();
 
             Ok(packages)
         })
@@ -37,17 +67,19 @@ impl AptPackageManager {
     ///
     /// # Arguments
     /// * `terminal` - Terminal emulator to use (e.g., "cosmic-term", "konsole")
+    /// * `strategy` - Configured upgrade options (subcommand, prompts, extra flags)
     ///
     /// # Returns
     /// * `Ok(())` - Terminal process spawned successfully
-    /// * `Err(String)` - Failed to spawn terminal
-    pub async fn run_upgrade(&self, terminal: &str) -> Result<(), String> {
+    /// * `Err(String)` - Failed to spawn terminal, or an extra flag is not allowed
+    pub async fn run_upgrade(&self, terminal: &str, strategy: &UpgradeStrategy) -> Result<(), String> {
         let terminal = terminal.to_string();
+        let upgrade = strategy.apt_command()?.join(" ");
         task::spawn_blocking(move || {
             // Use bash -c to chain the upgrade command with a prompt to keep terminal open
-            let command = "pkexec apt upgrade -y; echo ''; echo 'Press Enter to close...'; read";
+            let command = format!("{}; echo ''; echo 'Press Enter to close...'; read", upgrade);
             StdCommand::new(&terminal)
-                .args(["-e", "bash", "-c", command])
+                .args(["-e", "bash", "-c", &command])
                 .spawn()
                 .map_err(|e| format!("Failed to launch terminal '{}': {}", terminal, e))?;
 
@@ -57,6 +89,257 @@ impl AptPackageManager {
         .map_err(|e| format!("Task join error: {}", e))?
     }
 
+    /// Upgrade only the given packages without any interaction
+    ///
+    /// Used by the privileged helper for unattended updates, so it must already
+    /// run as root. Packages that are not installed are never pulled in
+    /// (`--only-upgrade`), and existing configuration files are kept. The
+    /// upgrade is simulated first and refused if APT would remove any package
+    /// to resolve it, since nobody is watching.
+    ///
+    /// # Arguments
+    /// * `names` - Names of the installed packages to upgrade
+    pub async fn upgrade_packages(&self, names: Vec<String>) -> Result<(), String> {
+        task::spawn_blocking(move || {
+            let run = |simulate: bool| {
+                let mut command = StdCommand::new("apt-get");
+                command.env("DEBIAN_FRONTEND", "noninteractive").args([
+                    "install",
+                    "--only-upgrade",
+                    "-y",
+                    "-o",
+                    "Dpkg::Options::=--force-confdef",
+                    "-o",
+                    "Dpkg::Options::=--force-confold",
+                ]);
+                if simulate {
+                    command.env("LC_ALL", "C").arg("-s");
+                }
+                command
+                    .args(&names)
+                    .output()
+                    .map_err(|e| format!("Failed to run apt-get: {}", e))
+            };
+
+            // "Remv <package> [<version>]" lines list what the upgrade would remove
+            let simulation = run(true)?;
+            if !simulation.status.success() {
+                let stderr = String::from_utf8_lossy(&simulation.stderr);
+                return Err(format!("Upgrade simulation failed: {}", stderr));
+            }
+            let removals: Vec<String> = String::from_utf8_lossy(&simulation.stdout)
+                .lines()
+                .filter_map(|line| line.strip_prefix("Remv "))
+                .filter_map(|rest| rest.split_whitespace().next())
+                .map(|name| name.to_string())
+                .collect();
+            if !removals.is_empty() {
+                return Err(format!(
+                    "Upgrade would remove {}, refusing to run it unattended",
+                    removals.join(", ")
+                ));
+            }
+
+            let output = run(false)?;
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                return Err(format!("Upgrade failed: {}", stderr));
+            }
+
+            Ok(())
+        })
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+    }
+
+    /// Fetch the changelog entries between the installed and the new version
+    ///
+    /// Uses `apt-get changelog`, which downloads the candidate's changelog. When
+    /// that fails (offline, or no changelog server for the origin) it falls back
+    /// to the installed `/usr/share/doc/<pkg>/changelog.Debian.gz`.
+    pub async fn changelog(&self, package: &Package) -> Result<Vec<ChangelogEntry>, String> {
+        let package = package.clone();
+        task::spawn_blocking(move || {
+            let text = StdCommand::new("apt-get")
+                .args(["changelog", &package.name])
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+                .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
+                .or_else(|| {
+                    let path = format!("/usr/share/doc/{}/changelog.Debian.gz", package.name);
+                    StdCommand::new("zcat")
+                        .arg(path)
+                        .output()
+                        .ok()
+                        .filter(|output| output.status.success())
+                        .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
+                })
+                .ok_or_else(|| format!("No changelog available for {}", package.name))?;
+
+            Ok(changelog::cut_to_range(
+                changelog::parse_debian(&text),
+                &package.current_version,
+                &package.new_version,
+            ))
+        })
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+    }
+
+    /// Collect metadata for the package detail page from `apt-cache show`
+    ///
+    /// The new version's record provides description, homepage and maintainer.
+    /// The installed version's record is used to work out dependency changes.
+    /// Licences come from the machine-readable copyright file, when there is one.
+    pub async fn details(&self, package: &Package) -> Result<PackageDetails, String> {
+        let package = package.clone();
+        task::spawn_blocking(move || {
+            let new = details::parse_debian_control(&apt_cache_show(&package.name, &package.new_version)?);
+            let installed = apt_cache_show(&package.name, &package.current_version)
+                .map(|output| details::parse_debian_control(&output))
+                .unwrap_or_default();
+
+            let description = match details::field(&new, "Description") {
+                "" => details::field(&new, "Description-en"),
+                description => description,
+            };
+
+            let dependencies = |fields: &[(String, String)]| {
+                let mut deps = details::debian_dependencies(details::field(fields, "Pre-Depends"));
+                deps.extend(details::debian_dependencies(details::field(fields, "Depends")));
+                deps
+            };
+
+            // Size is in bytes, Installed-Size in KiB
+            let size = |value: &str, unit: u64| {
+                value
+                    .parse::<u64>()
+                    .ok()
+                    .map(|value| utils::format_size(value * unit))
+                    .unwrap_or_default()
+            };
+
+            let mut result = PackageDetails {
+                description: description.to_string(),
+                repository: match package.origin.as_str() {
+                    "" => package.repository.replace(',', ", "),
+                    origin => format!("{} ({})", package.repository.replace(',', ", "), origin),
+                },
+                url: details::field(&new, "Homepage").to_string(),
+                licenses: copyright_licenses(&package.name),
+                packager: details::field(&new, "Maintainer").to_string(),
+                download_size: size(details::field(&new, "Size"), 1),
+                installed_size: size(details::field(&new, "Installed-Size"), 1024),
+                ..Default::default()
+            };
+            result.set_dependency_changes(&dependencies(&installed), &dependencies(&new));
+
+            Ok(result)
+        })
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+    }
+
+    /// Launch an upgrade that may install or remove packages in a terminal
+    ///
+    /// Used for packages `apt upgrade` keeps back. Without a name this runs
+    /// `apt full-upgrade`, with a name `apt install <name>`. Both stay
+    /// interactive so the user can review the extra installs and removals.
+    ///
+    /// # Arguments
+    /// * `terminal` - Terminal emulator to use
+    /// * `name` - Kept-back package to install, or None for a full upgrade
+    pub async fn run_kept_back_upgrade(&self, terminal: &str, name: Option<String>) -> Result<(), String> {
+        let terminal = terminal.to_string();
+        task::spawn_blocking(move || {
+            let action = match name {
+                Some(name) => {
+                    // The name ends up in a shell command line
+                    if !name.chars().all(|c| c.is_ascii_alphanumeric() || "+-.:_".contains(c)) {
+                        return Err(format!("Invalid package name: {}", name));
+                    }
+                    format!("pkexec apt install {}", name)
+                }
+                None => "pkexec apt full-upgrade".to_string(),
+            };
+            let command = format!("{}; echo ''; echo 'Press Enter to close...'; read", action);
+            StdCommand::new(&terminal)
+                .args(["-e", "bash", "-c", &command])
+                .spawn()
+                .map_err(|e| format!("Failed to launch terminal '{}': {}", terminal, e))?;
+
+            Ok(())
+        })
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+    }
+
+    /// Read the packages marked with `apt-mark hold`
+    ///
+    /// # Returns
+    /// (name, source) pairs
+    pub async fn holds(&self) -> Vec<(String, String)> {
+        task::spawn_blocking(|| {
+            StdCommand::new("apt-mark")
+                .arg("showhold")
+                .output()
+                .map(|output| {
+                    String::from_utf8_lossy(&output.stdout)
+                        .lines()
+                        .map(|line| line.trim())
+                        .filter(|line| !line.is_empty())
+                        .map(|name| (name.to_string(), "apt-mark hold".to_string()))
+                        .collect()
+                })
+                .unwrap_or_default()
+        })
+        .await
+        .unwrap_or_default()
+    }
+
+    /// Hold or release a package with `apt-mark` through pkexec
+    pub async fn set_hold(&self, name: String, hold: bool) -> Result<(), String> {
+        task::spawn_blocking(move || {
+            let action = if hold { "hold" } else { "unhold" };
+            let output = StdCommand::new("pkexec")
+                .args(["apt-mark", action, &name])
+                .output()
+                .map_err(|e| format!("Failed to run apt-mark: {}", e))?;
+
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                return Err(format!("apt-mark {} failed: {}", action, stderr));
+            }
+
+            Ok(())
+        })
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+    }
+
+    /// Download pending upgrades into the APT archive cache without installing them
+    ///
+    /// Runs `apt-get -d upgrade` through pkexec. A later `apt upgrade` finds the
+    /// packages in `/var/cache/apt/archives` and only has to install them.
+    pub async fn download_updates(&self) -> Result<(), String> {
+        task::spawn_blocking(|| {
+            let output = StdCommand::new("pkexec")
+                .args(["apt-get", "-d", "-y", "upgrade"])
+                .output()
+                .map_err(|e| format!("Failed to download updates: {}", e))?;
+
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                return Err(format!("Download failed: {}", stderr));
+            }
+
+            Ok(())
+        })
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+    }
+
     pub async fn is_running(&self) -> bool {
         task::spawn_blocking(|| {
             // Check both lock files and running processes
@@ -82,72 +365,136 @@ impl AptPackageManager {
         "APT"
     }
 
-    pub async fn refresh_cache(&self) -> Result<(), String> {
+    /// Download fresh package lists with `apt update` through pkexec
+    ///
+    /// # Returns
+    /// * `Ok(Vec<RepositoryIssue>)` - Warnings and errors apt reported per repository.
+    ///   apt fails as a whole when one repository fails, which is reported as an issue too
+    /// * `Err(String)` - apt could not run at all, or failed without naming a repository
+    pub async fn refresh_cache(&self) -> Result<Vec<RepositoryIssue>, String> {
         task::spawn_blocking(|| {
             let output = StdCommand::new("pkexec")
                 .args(["apt", "update"])
+                .env("LC_ALL", "C") // Messages are parsed below
                 .output()
                 .map_err(|e| format!("Failed to refresh cache: {}", e))?;
 
-            if !output.status.success() {
-                let stderr = String::from_utf8_lossy(&output.stderr);
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let issues = repo_health::parse_apt_update(&format!("{}\n{}", stdout, stderr));
+
+            if !output.status.success() && issues.is_empty() {
                 return Err(format!("Cache refresh failed: {}", stderr));
             }
 
-            Ok(())
+            Ok(issues)
         })
         .await
         .map_err(|e| format!("Task join error: {}", e))?
     }
 }
 
-/// Parse APT output into a list of packages
+/// What a simulated `apt-get upgrade` would leave out
+#[derive(Debug, Default)]
+struct UpgradeSimulation {
+    /// Updates APT is still phasing in and defers for now
+    deferred: Vec<String>,
+    /// Updates that need new packages or removals, which `apt upgrade` never does
+    kept_back: Vec<String>,
+}
+
+/// Simulate `apt-get upgrade` (no root needed) and read what it would skip
+fn simulate_upgrade() -> UpgradeSimulation {
+    let Ok(output) = StdCommand::new("apt-get")
+        .args(["-s", "upgrade"])
+        .env("LC_ALL", "C") // Section headers are parsed below
+        .output()
+    else {
+        return UpgradeSimulation::default();
+    };
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    UpgradeSimulation {
+        deferred: simulation_section(&stdout, "The following upgrades have been deferred due to phasing:"),
+        kept_back: simulation_section(&stdout, "The following packages have been kept back:"),
+    }
+}
+
+/// Explain why `apt upgrade` keeps a package back
+///
+/// Simulates `apt-get install <name>` and reports the new packages it would
+/// pull in and the packages it would remove.
+fn kept_back_reason(name: &str) -> String {
+    let Ok(output) = StdCommand::new("apt-get")
+        .args(["-s", "install", name])
+        .env("LC_ALL", "C")
+        .output()
+    else {
+        return String::new();
+    };
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let new = simulation_section(&stdout, "The following NEW packages will be installed:");
+    let removed = simulation_section(&stdout, "The following packages will be REMOVED:");
+
+    let mut reasons = Vec::new();
+    if !new.is_empty() {
+        reasons.push(format!("needs new packages: {}", new.join(", ")));
+    }
+    if !removed.is_empty() {
+        reasons.push(format!("would remove: {}", removed.join(", ")));
+    }
+    if reasons.is_empty() {
+        reasons.push("dependencies cannot be satisfied yet".to_string());
+    }
+    reasons.join("; ")
+}
+
+/// Collect the package names listed under a header of apt-get's output
 ///
-/// Expected format: "package/repo version [architecture] [upgradable from: old_version]"
-/// Example: "firefox/jammy-updates 121.0+build1-0ubuntu0.22.04.1 amd64 [upgradable from: 120.0+build2-0ubuntu0.22.04.1]"
-fn parse_apt_output(output: &str) -> Vec<Package> {
+/// Names follow the header on indented lines, several per line.
+fn simulation_section(output: &str, header: &str) -> Vec<String> {
     output
         .lines()
-        .skip(1) // Skip header line
-        .filter_map(|line| {
-            // Only process lines that contain the upgradable marker
-            if !line.contains("[upgradable from:") {
-                return None;
-            }
+        .skip_while(|line| line.trim_end() != header)
+        .skip(1)
+        .take_while(|line| line.starts_with(' '))
+        .flat_map(|line| line.split_whitespace())
+        .map(|name| name.to_string())
+        .collect()
+}
 
-            let parts: Vec<&str> = line.split_whitespace().collect();
+/// Print the APT record of a specific package version
+fn apt_cache_show(name: &str, version: &str) -> Result<String, String> {
+    let output = StdCommand::new("apt-cache")
+        .args(["show", &format!("{}={}", name, version)])
+        .output()
+        .map_err(|e| format!("Failed to run apt-cache: {}", e))?;
 
-            // Validate we have enough parts for a complete package entry
-            if parts.len() < 6 {
-                eprintln!("Warning: Skipping malformed APT line (insufficient fields): {}", line);
-                return None;
-            }
+    if !output.status.success() {
+        return Err(format!("No record for {} {}", name, version));
+    }
 
-            // Extract package name (before the '/')
-            let name = parts.first()?.split('/').next()?.to_string();
-            if name.is_empty() {
-                eprintln!("Warning: Skipping APT line with empty package name: {}", line);
-                return None;
-            }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
 
-            // Extract new version
-            let new_version = parts.get(1)?.to_string();
+/// Read licence names from a machine-readable Debian copyright file
+fn copyright_licenses(name: &str) -> String {
+    let path = format!("/usr/share/doc/{}/copyright", name);
+    let Ok(contents) = fs::read_to_string(path) else {
+        return String::new();
+    };
 
-            // Extract current version (remove trailing ']')
-            let current_version = parts.get(5)?.trim_end_matches(']').to_string();
-            if current_version.is_empty() {
-                eprintln!("Warning: Skipping APT line with empty version: {}", line);
-                return None;
+    let mut licenses: Vec<&str> = Vec::new();
+    for line in contents.lines() {
+        if let Some(license) = line.strip_prefix("License:") {
+            let license = license.trim();
+            if !license.is_empty() && !licenses.contains(&license) {
+                licenses.push(license);
             }
-
-            Some(Package {
-                name,
-                current_version,
-                new_version,
-                is_aur: false,
-            })
-        })
-        .collect()
+        }
+    }
+    licenses.join(", ")
 }
 
 fn get_debug_packages() -> Vec<Package> {
@@ -157,54 +504,67 @@ fn get_debug_packages() -> Vec<Package> {
             current_version: "120.0".to_string(),
             new_version: "121.0".to_string(),
             is_aur: false,
+            ..Default::default()
         },
         Package {
             name: "libcosmic".to_string(),
             current_version: "0.1.0".to_string(),
             new_version: "0.2.0".to_string(),
             is_aur: false,
+            ..Default::default()
         },
         Package {
             name: "rust-analyzer".to_string(),
             current_version: "2024-01-01".to_string(),
             new_version: "2024-02-01".to_string(),
             is_aur: false,
+            ..Default::default()
         },
         Package {
             name: "linux-image-generic".to_string(),
             current_version: "6.5.0.14".to_string(),
             new_version: "6.5.0.15".to_string(),
             is_aur: false,
+            ..Default::default()
         },
         Package {
             name: "systemd".to_string(),
             current_version: "255.2-1".to_string(),
             new_version: "255.4-1".to_string(),
             is_aur: false,
+            is_security: true,
+            repository: "noble-updates,noble-security".to_string(),
+            ..Default::default()
         },
         Package {
             name: "libc6".to_string(),
             current_version: "2.39-0ubuntu8".to_string(),
             new_version: "2.39-0ubuntu8.1".to_string(),
             is_aur: false,
+            ..Default::default()
         },
         Package {
             name: "python3".to_string(),
             current_version: "3.12.3-0".to_string(),
             new_version: "3.12.4-0".to_string(),
             is_aur: false,
+            ..Default::default()
         },
         Package {
             name: "curl".to_string(),
             current_version: "8.5.0-2".to_string(),
             new_version: "8.6.0-1".to_string(),
             is_aur: false,
+            is_security: true,
+            repository: "noble-security".to_string(),
+            ..Default::default()
         },
         Package {
             name: "git".to_string(),
             current_version: "2.43.0".to_string(),
             new_version: "2.44.0".to_string(),
             is_aur: false,
+            ..Default::default()
         },
     ]
 }
@@ -0,0 +1,603 @@
+use super::version;
+use crate::utils;
+use flate2::read::GzDecoder;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command as StdCommand;
+
+/// dpkg's database of installed packages
+pub const DPKG_STATUS: &str = "/var/lib/dpkg/status";
+
+/// Pin files, read in this order (the directory sorted by name)
+const APT_PREFERENCES: &str = "/etc/apt/preferences";
+const APT_PREFERENCES_DIR: &str = "/etc/apt/preferences.d";
+
+/// APT configuration that may set `APT::Default-Release`
+const APT_CONF: &str = "/etc/apt/apt.conf";
+const APT_CONF_DIR: &str = "/etc/apt/apt.conf.d";
+
+/// Helper shipped with APT that decompresses indices in any supported format
+const APT_HELPER: &str = "/usr/lib/apt/apt-helper";
+
+/// Priority of versions from an ordinary archive
+const DEFAULT_PRIORITY: i32 = 500;
+/// Priority of versions from the `APT::Default-Release` archive
+const TARGET_RELEASE_PRIORITY: i32 = 990;
+/// Priority of the installed version, and of `ButAutomaticUpgrades` archives
+const INSTALLED_PRIORITY: i32 = 100;
+/// Priority of `NotAutomatic` archives such as backports and experimental
+const NOT_AUTOMATIC_PRIORITY: i32 = 1;
+/// Pins at or above this priority allow downgrades
+const DOWNGRADE_PRIORITY: i32 = 1000;
+
+/// Header paragraph of an archive's Release or InRelease file
+#[derive(Debug, Clone, Default)]
+pub struct Release {
+    pub origin: String,
+    pub label: String,
+    pub suite: String,
+    pub codename: String,
+    pub version: String,
+    /// Host name of the archive URI, matched by `Pin: origin`
+    pub host: String,
+    /// Versions are only installed on request (`NotAutomatic: yes`)
+    pub not_automatic: bool,
+    /// Installed packages still follow upgrades (`ButAutomaticUpgrades: yes`)
+    pub but_automatic_upgrades: bool,
+}
+
+impl Release {
+    /// True for Debian's and Ubuntu's security archives
+    ///
+    /// Debian's security archive uses suites such as "stable-security", so the
+    /// origin is checked as well as the pocket name.
+    pub fn is_security(&self) -> bool {
+        self.origin == "Debian-Security"
+            || self.label == "Debian-Security"
+            || self.suite.ends_with("-security")
+            || self.codename.ends_with("-security")
+    }
+
+    /// Name shown for the archive, the suite or else the codename
+    pub fn name(&self) -> &str {
+        if self.suite.is_empty() {
+            &self.codename
+        } else {
+            &self.suite
+        }
+    }
+}
+
+/// The version of an installed package APT would upgrade to
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub name: String,
+    pub architecture: String,
+    pub installed_version: String,
+    pub version: String,
+    /// Origin of the first archive providing the version, e.g. "Ubuntu"
+    pub origin: String,
+    /// Suites providing the version, e.g. ["noble-updates", "noble-security"]
+    pub suites: Vec<String>,
+    /// One of the archives providing the version is a security archive
+    pub is_security: bool,
+    /// Size of the .deb in bytes
+    pub download_size: u64,
+    /// Size once installed in bytes
+    pub installed_size: u64,
+    /// Pin priority that made this version the candidate
+    pub priority: i32,
+    /// Rollout share of a phased update
+    pub phased_percentage: Option<u32>,
+}
+
+/// Installed versions by (name, architecture)
+type Installed = HashMap<(String, String), String>;
+
+/// One version of a package offered by an index
+#[derive(Debug, Clone)]
+struct Available {
+    version: String,
+    /// Index into the list of releases, None for the dpkg status file
+    release: Option<usize>,
+    component: String,
+    architecture: String,
+    download_size: u64,
+    installed_size: u64,
+    phased_percentage: Option<u32>,
+}
+
+/// What a pin applies to
+#[derive(Debug, Clone)]
+enum PinTarget {
+    /// `Pin: release a=noble-backports, o=Ubuntu`
+    Release(Vec<(String, String)>),
+    /// `Pin: origin ppa.launchpadcontent.net`
+    Origin(String),
+    /// `Pin: version 1.2*`
+    Version(String),
+}
+
+/// A stanza of /etc/apt/preferences
+#[derive(Debug, Clone)]
+struct Pin {
+    packages: Vec<String>,
+    target: PinTarget,
+    priority: i32,
+}
+
+impl Pin {
+    /// True when the Package line names the package itself instead of a pattern
+    fn is_specific(&self, name: &str) -> bool {
+        self.packages.iter().any(|package| package == name)
+    }
+
+    fn matches_package(&self, name: &str) -> bool {
+        self.packages.iter().any(|pattern| {
+            if pattern.starts_with('/') {
+                false // Regular expressions are not supported
+            } else {
+                utils::glob_match(pattern, name)
+            }
+        })
+    }
+
+    fn matches(&self, available: &Available, release: Option<&Release>) -> bool {
+        match &self.target {
+            PinTarget::Version(pattern) => utils::glob_match(pattern, &available.version),
+            PinTarget::Origin(host) => release.map_or("", |r| r.host.as_str()) == host,
+            PinTarget::Release(conditions) => {
+                let Some(release) = release else {
+                    return false;
+                };
+                conditions.iter().all(|(key, value)| {
+                    let actual = match key.as_str() {
+                        "a" | "archive" => &release.suite,
+                        "n" | "codename" => &release.codename,
+                        "v" | "version" => &release.version,
+                        "o" | "origin" => &release.origin,
+                        "l" | "label" => &release.label,
+                        "c" | "component" => &available.component,
+                        "b" | "architecture" => &available.architecture,
+                        _ => return false,
+                    };
+                    utils::glob_match(value, actual)
+                })
+            }
+        }
+    }
+}
+
+/// Work out which installed packages APT would upgrade, and to which version
+///
+/// Reads the installed versions from the dpkg status file and every `Packages`
+/// index in the lists directory, then picks each package's candidate the way
+/// APT's policy does: highest pin priority first, then highest version, and
+/// no downgrades below priority 1000.
+///
+/// # Arguments
+/// * `lists_dir` - Directory with the downloaded indices, e.g. "/var/lib/apt/lists"
+///
+/// # Returns
+/// * `Ok(Vec<Candidate>)` - Installed packages with a newer candidate, sorted by name
+/// * `Err(String)` - The dpkg status file or the lists directory could not be read
+pub fn upgradable(lists_dir: &Path) -> Result<Vec<Candidate>, String> {
+    let (installed, native_arch) = read_status(Path::new(DPKG_STATUS))?;
+    let releases = read_releases(lists_dir)?;
+    let pins = read_preferences();
+    let default_release = read_default_release();
+
+    // Every version the indices offer for the installed packages
+    let mut available: HashMap<(String, String), Vec<Available>> = HashMap::new();
+    for (index_path, release, component) in package_indices(lists_dir, &releases)? {
+        let contents = match read_index(&index_path) {
+            Ok(contents) => contents,
+            Err(e) => {
+                eprintln!("Warning: {}", e);
+                continue;
+            }
+        };
+        for_each_stanza(&contents, |fields| {
+            let (Some(name), Some(version), Some(architecture)) =
+                (field(fields, "Package"), field(fields, "Version"), field(fields, "Architecture"))
+            else {
+                return;
+            };
+            let key = (name.to_string(), architecture.to_string());
+            if !installed.contains_key(&key) {
+                return;
+            }
+            available.entry(key).or_default().push(Available {
+                version: version.to_string(),
+                release,
+                component: component.clone(),
+                architecture: architecture.to_string(),
+                download_size: field(fields, "Size").and_then(|s| s.parse().ok()).unwrap_or(0),
+                installed_size: field(fields, "Installed-Size")
+                    .and_then(|s| s.parse::<u64>().ok())
+                    .map(|kib| kib * 1024)
+                    .unwrap_or(0),
+                phased_percentage: field(fields, "Phased-Update-Percentage").and_then(|s| s.parse().ok()),
+            });
+        });
+    }
+
+    let priority_of = |name: &str, candidate: &Available| {
+        let release = candidate.release.map(|index| &releases[index].1);
+        let pinned = pins
+            .iter()
+            .filter(|pin| pin.is_specific(name))
+            .chain(pins.iter().filter(|pin| !pin.is_specific(name)))
+            .find(|pin| pin.matches_package(name) && pin.matches(candidate, release));
+        if let Some(pin) = pinned {
+            return pin.priority;
+        }
+
+        match release {
+            None => INSTALLED_PRIORITY,
+            Some(release) if release.not_automatic && release.but_automatic_upgrades => INSTALLED_PRIORITY,
+            Some(release) if release.not_automatic => NOT_AUTOMATIC_PRIORITY,
+            Some(release)
+                if default_release
+                    .as_deref()
+                    .is_some_and(|target| target == release.suite || target == release.codename) =>
+            {
+                TARGET_RELEASE_PRIORITY
+            }
+            Some(_) => DEFAULT_PRIORITY,
+        }
+    };
+
+    let mut candidates = Vec::new();
+    for ((name, architecture), installed_version) in &installed {
+        let Some(offers) = available.get(&(name.clone(), architecture.clone())) else {
+            continue;
+        };
+
+        // A version's priority is the highest among the archives offering it,
+        // and the installed version always counts with at least 100
+        let mut versions: Vec<(&str, i32)> = vec![(installed_version, INSTALLED_PRIORITY)];
+        for offer in offers {
+            let priority = priority_of(name, offer);
+            match versions.iter_mut().find(|(version, _)| *version == offer.version) {
+                Some(entry) => entry.1 = entry.1.max(priority),
+                None => versions.push((&offer.version, priority)),
+            }
+        }
+
+        // Lower versions only count with a downgrade pin, and negative priorities never
+        let Some(&(best, priority)) = versions
+            .iter()
+            .filter(|(version, priority)| {
+                *priority >= 0
+                    && (*priority >= DOWNGRADE_PRIORITY
+                        || version::compare_debian(version, installed_version) != Ordering::Less)
+            })
+            .max_by(|(a_version, a_priority), (b_version, b_priority)| {
+                a_priority
+                    .cmp(b_priority)
+                    .then_with(|| version::compare_debian(a_version, b_version))
+            })
+        else {
+            continue;
+        };
+        if version::compare_debian(best, installed_version) != Ordering::Greater {
+            continue;
+        }
+
+        let providers: Vec<&Available> = offers.iter().filter(|offer| offer.version == best).collect();
+        let provider_releases: Vec<&Release> = providers
+            .iter()
+            .filter_map(|offer| offer.release.map(|index| &releases[index].1))
+            .collect();
+        let mut suites: Vec<String> = Vec::new();
+        for release in &provider_releases {
+            if !suites.iter().any(|suite| suite == release.name()) {
+                suites.push(release.name().to_string());
+            }
+        }
+        let first = providers[0];
+
+        candidates.push(Candidate {
+            name: if architecture == &native_arch || architecture == "all" {
+                name.clone()
+            } else {
+                format!("{}:{}", name, architecture)
+            },
+            architecture: architecture.clone(),
+            installed_version: installed_version.clone(),
+            version: best.to_string(),
+            origin: provider_releases.first().map(|r| r.origin.clone()).unwrap_or_default(),
+            suites,
+            is_security: provider_releases.iter().any(|release| release.is_security()),
+            download_size: first.download_size,
+            installed_size: first.installed_size,
+            priority,
+            phased_percentage: first.phased_percentage,
+        });
+    }
+
+    candidates.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(candidates)
+}
+
+/// Read the installed packages from the dpkg status file
+///
+/// # Returns
+/// Installed versions by (name, architecture), and the native architecture
+/// (the one dpkg itself is built for)
+fn read_status(path: &Path) -> Result<(Installed, String), String> {
+    let contents = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+    let mut installed = HashMap::new();
+    let mut native_arch = String::new();
+    for_each_stanza(&contents, |fields| {
+        let (Some(name), Some(version), Some(architecture), Some(status)) = (
+            field(fields, "Package"),
+            field(fields, "Version"),
+            field(fields, "Architecture"),
+            field(fields, "Status"),
+        ) else {
+            return;
+        };
+        // "install ok installed", "hold ok installed", ...
+        if status.split_whitespace().nth(2) != Some("installed") {
+            return;
+        }
+        if name == "dpkg" {
+            native_arch = architecture.to_string();
+        }
+        installed.insert((name.to_string(), architecture.to_string()), version.to_string());
+    });
+
+    Ok((installed, native_arch))
+}
+
+/// Read the Release and InRelease files in the lists directory
+///
+/// # Returns
+/// (file name prefix, release) pairs, e.g. ("archive.ubuntu.com_ubuntu_dists_noble_", ...).
+/// The indices of an archive share its prefix.
+pub fn read_releases(lists_dir: &Path) -> Result<Vec<(String, Release)>, String> {
+    let entries =
+        fs::read_dir(lists_dir).map_err(|e| format!("Failed to read {}: {}", lists_dir.display(), e))?;
+
+    let mut releases = Vec::new();
+    for entry in entries.flatten() {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let Some(prefix) = file_name
+            .strip_suffix("InRelease")
+            .or_else(|| file_name.strip_suffix("Release"))
+        else {
+            continue;
+        };
+        // Both files may exist for one archive, InRelease is enough
+        if releases.iter().any(|(known, _): &(String, Release)| known == prefix) {
+            continue;
+        }
+        let Ok(contents) = fs::read_to_string(entry.path()) else {
+            continue;
+        };
+
+        let mut release = parse_release(&contents);
+        release.host = prefix.split('_').next().unwrap_or("").to_string();
+        releases.push((prefix.to_string(), release));
+    }
+
+    Ok(releases)
+}
+
+/// Parse the header paragraph of a Release file, skipping an OpenPGP signature header
+pub fn parse_release(contents: &str) -> Release {
+    let mut lines = contents.lines().peekable();
+    if lines.peek().is_some_and(|line| line.starts_with("-----BEGIN PGP SIGNED MESSAGE")) {
+        // "Hash: ..." lines follow up to a blank line
+        lines.by_ref().take_while(|line| !line.trim().is_empty()).for_each(drop);
+    }
+
+    let mut release = Release::default();
+    for line in lines.take_while(|line| !line.trim().is_empty()) {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim().to_string();
+        match key {
+            "Origin" => release.origin = value,
+            "Label" => release.label = value,
+            "Suite" => release.suite = value,
+            "Codename" => release.codename = value,
+            "Version" => release.version = value,
+            "NotAutomatic" => release.not_automatic = value == "yes",
+            "ButAutomaticUpgrades" => release.but_automatic_upgrades = value == "yes",
+            _ => {}
+        }
+    }
+    release
+}
+
+/// List the binary package indices with their release and component
+///
+/// Index names look like "<prefix>main_binary-amd64_Packages", possibly with a
+/// compression suffix. The release is the one with the longest matching prefix.
+fn package_indices(
+    lists_dir: &Path,
+    releases: &[(String, Release)],
+) -> Result<Vec<(PathBuf, Option<usize>, String)>, String> {
+    let entries =
+        fs::read_dir(lists_dir).map_err(|e| format!("Failed to read {}: {}", lists_dir.display(), e))?;
+
+    let mut indices = Vec::new();
+    for entry in entries.flatten() {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let is_index = file_name.ends_with("_Packages")
+            || file_name
+                .rsplit_once("_Packages.")
+                .is_some_and(|(_, extension)| !extension.contains('_'));
+        if !is_index {
+            continue;
+        }
+
+        let release = releases
+            .iter()
+            .enumerate()
+            .filter(|(_, (prefix, _))| file_name.starts_with(prefix.as_str()))
+            .max_by_key(|(_, (prefix, _))| prefix.len());
+        let component = match release {
+            // Flat repositories have no component, their index follows the prefix directly
+            Some((_, (prefix, _))) => file_name[prefix.len()..]
+                .split_once('_')
+                .filter(|(component, _)| *component != "Packages" && !component.starts_with("binary-"))
+                .map(|(component, _)| component.to_string())
+                .unwrap_or_default(),
+            None => String::new(),
+        };
+
+        indices.push((entry.path(), release.map(|(index, _)| index), component));
+    }
+
+    Ok(indices)
+}
+
+/// Read an index, decompressing it if APT was told to keep it compressed
+fn read_index(path: &Path) -> Result<String, String> {
+    let raw = fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+    if raw.starts_with(&[0x1f, 0x8b]) {
+        let mut contents = String::new();
+        GzDecoder::new(raw.as_slice())
+            .read_to_string(&mut contents)
+            .map_err(|e| format!("Failed to decompress {}: {}", path.display(), e))?;
+        return Ok(contents);
+    }
+    if path.extension().is_none() {
+        return Ok(String::from_utf8_lossy(&raw).to_string());
+    }
+
+    // lz4, xz, zstd, ...
+    let output = StdCommand::new(APT_HELPER)
+        .arg("cat-file")
+        .arg(path)
+        .output()
+        .map_err(|e| format!("Failed to run apt-helper: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("Failed to decompress {}", path.display()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Read the pins from /etc/apt/preferences and /etc/apt/preferences.d
+///
+/// Files in the directory are read in name order and must have no extension
+/// or ".pref", like APT requires.
+fn read_preferences() -> Vec<Pin> {
+    let mut files = vec![Path::new(APT_PREFERENCES).to_path_buf()];
+    if let Ok(entries) = fs::read_dir(APT_PREFERENCES_DIR) {
+        let mut names: Vec<_> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_none_or(|extension| extension == "pref"))
+            .collect();
+        names.sort();
+        files.extend(names);
+    }
+
+    let mut pins = Vec::new();
+    for file in files {
+        let Ok(contents) = fs::read_to_string(&file) else {
+            continue;
+        };
+        for_each_stanza(&contents, |fields| {
+            let (Some(packages), Some(pin), Some(priority)) =
+                (field(fields, "Package"), field(fields, "Pin"), field(fields, "Pin-Priority"))
+            else {
+                return;
+            };
+            let Ok(priority) = priority.parse() else {
+                eprintln!("Warning: Invalid Pin-Priority in {}: {}", file.display(), priority);
+                return;
+            };
+            let (kind, value) = pin.split_once(' ').unwrap_or((pin, ""));
+            let value = value.trim();
+            let target = match kind {
+                "release" => PinTarget::Release(
+                    value
+                        .split(',')
+                        .filter_map(|condition| condition.trim().split_once('='))
+                        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+                        .collect(),
+                ),
+                "origin" => PinTarget::Origin(value.trim_matches('"').to_string()),
+                "version" => PinTarget::Version(value.to_string()),
+                _ => {
+                    eprintln!("Warning: Unsupported pin in {}: {}", file.display(), pin);
+                    return;
+                }
+            };
+            pins.push(Pin {
+                packages: packages.split_whitespace().map(|p| p.to_string()).collect(),
+                target,
+                priority,
+            });
+        });
+    }
+
+    pins
+}
+
+/// Read `APT::Default-Release` from the APT configuration
+fn read_default_release() -> Option<String> {
+    let mut files = vec![Path::new(APT_CONF).to_path_buf()];
+    if let Ok(entries) = fs::read_dir(APT_CONF_DIR) {
+        let mut names: Vec<_> = entries.flatten().map(|entry| entry.path()).collect();
+        names.sort();
+        files.extend(names);
+    }
+
+    // The last setting wins, as files are read in order
+    files
+        .iter()
+        .filter_map(|file| fs::read_to_string(file).ok())
+        .flat_map(|contents| {
+            contents
+                .lines()
+                .filter_map(|line| {
+                    let value = line.trim().strip_prefix("APT::Default-Release")?;
+                    let value = value.trim().trim_end_matches(';').trim().trim_matches('"');
+                    (!value.is_empty()).then(|| value.to_string())
+                })
+                .collect::<Vec<_>>()
+        })
+        .last()
+}
+
+/// Call `handle` with the fields of every stanza in a control file
+///
+/// Continuation lines are skipped, only single-line values are needed here.
+pub fn for_each_stanza<'a>(contents: &'a str, mut handle: impl FnMut(&[(&'a str, &'a str)])) {
+    let mut fields: Vec<(&str, &str)> = Vec::new();
+
+    for line in contents.lines() {
+        if line.trim().is_empty() {
+            if !fields.is_empty() {
+                handle(&fields);
+                fields.clear();
+            }
+        } else if line.starts_with(' ') || line.starts_with('\t') || line.starts_with('#') {
+            continue;
+        } else if let Some((key, value)) = line.split_once(':') {
+            fields.push((key, value.trim()));
+        }
+    }
+
+    if !fields.is_empty() {
+        handle(&fields);
+    }
+}
+
+/// Value of a field in a stanza passed to `for_each_stanza`
+pub fn field<'a>(fields: &[(&str, &'a str)], key: &str) -> Option<&'a str> {
+    fields.iter().find(|(k, _)| *k == key).map(|(_, value)| *value)
+}
@@ -0,0 +1,145 @@
+use super::version::compare_pacman;
+use super::Package;
+use crate::utils;
+use serde::Deserialize;
+use std::cmp::Ordering;
+use std::process::Command as StdCommand;
+use tokio::task;
+
+/// Default location of the Arch Linux security tracker's advisory group list
+pub const DEFAULT_TRACKER_URL: &str = "https://security.archlinux.org/all.json";
+
+/// Arch Vulnerability Group as published by the tracker and by `arch-audit --json`
+///
+/// Both formats share these fields. Anything else is ignored, and missing
+/// fields fall back to empty values so either source can be parsed.
+#[derive(Debug, Clone, Deserialize)]
+struct Avg {
+    /// AVG identifier (tracker) or package name (arch-audit)
+    #[serde(default)]
+    name: String,
+    /// Affected package names
+    #[serde(default)]
+    packages: Vec<String>,
+    /// "Vulnerable", "Fixed", "Not affected", ...
+    #[serde(default)]
+    status: String,
+    /// "Critical", "High", "Medium", "Low" or "Unknown"
+    #[serde(default)]
+    severity: String,
+    /// First version that contains the fix, if one exists
+    #[serde(default)]
+    fixed: Option<String>,
+    /// CVE identifiers covered by this group
+    #[serde(default)]
+    issues: Vec<String>,
+}
+
+impl Avg {
+    fn affects(&self, package: &str) -> bool {
+        if self.packages.is_empty() {
+            self.name == package
+        } else {
+            self.packages.iter().any(|p| p == package)
+        }
+    }
+}
+
+/// Mark pending updates that fix known vulnerabilities
+///
+/// For every package whose installed version is older than an advisory's fixed
+/// version, and whose new version includes the fix, the advisory's CVEs and
+/// severity are attached and the package is flagged as a security update.
+///
+/// # Arguments
+/// * `packages` - Pending updates from the pacman/AUR backends
+/// * `source` - "auto" (arch-audit if installed, otherwise the tracker),
+///   "arch-audit", or a tracker URL / local JSON file
+pub async fn annotate(packages: Vec<Package>, source: String) -> Vec<Package> {
+    // A panicking lookup must not drop the updates themselves
+    let unchanged = packages.clone();
+    task::spawn_blocking(move || {
+        let mut packages = packages;
+        match load_advisories(&source) {
+            Ok(advisories) => apply_advisories(&mut packages, &advisories),
+            Err(e) => eprintln!("Warning: Could not load Arch security advisories: {}", e),
+        }
+        packages
+    })
+    .await
+    .unwrap_or_else(|e| {
+        eprintln!("Warning: Security annotation failed: {}", e);
+        unchanged
+    })
+}
+
+/// Load advisory groups from arch-audit or the security tracker JSON
+fn load_advisories(source: &str) -> Result<Vec<Avg>, String> {
+    let json = match source {
+        "arch-audit" => run_arch_audit()?,
+        "auto" | "" => run_arch_audit().or_else(|_| utils::fetch_url(DEFAULT_TRACKER_URL))?,
+        other => utils::fetch_url(other)?,
+    };
+
+    serde_json::from_str(&json).map_err(|e| format!("Invalid advisory JSON: {}", e))
+}
+
+/// Run `arch-audit --json` and return its output
+fn run_arch_audit() -> Result<String, String> {
+    let output = StdCommand::new("arch-audit")
+        .arg("--json")
+        .output()
+        .map_err(|e| format!("Failed to run arch-audit: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("arch-audit failed: {}", stderr));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Attach CVEs and severity of every advisory a pending update fixes
+fn apply_advisories(packages: &mut [Package], advisories: &[Avg]) {
+    for package in packages.iter_mut() {
+        for avg in advisories.iter().filter(|a| a.affects(&package.name)) {
+            if avg.status == "Not affected" {
+                continue;
+            }
+            let Some(fixed) = &avg.fixed else {
+                continue;
+            };
+
+            // The installed version must be vulnerable and the new one fixed
+            let installed_vulnerable =
+                compare_pacman(&package.current_version, fixed) == Ordering::Less;
+            let update_fixes = compare_pacman(&package.new_version, fixed) != Ordering::Less;
+            if !installed_vulnerable || !update_fixes {
+                continue;
+            }
+
+            for cve in &avg.issues {
+                if !package.cves.contains(cve) {
+                    package.cves.push(cve.clone());
+                }
+            }
+            if package.severity.is_empty()
+                || severity_rank(&avg.severity) > severity_rank(&package.severity)
+            {
+                package.severity = avg.severity.clone();
+            }
+            package.is_security = true;
+        }
+    }
+}
+
+/// Order tracker severities so the highest one can be kept
+fn severity_rank(severity: &str) -> u8 {
+    match severity {
+        "Critical" => 4,
+        "High" => 3,
+        "Medium" => 2,
+        "Low" => 1,
+        _ => 0,
+    }
+}
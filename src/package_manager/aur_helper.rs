@@ -0,0 +1,420 @@
+use super::changelog::{self, ChangelogEntry};
+use super::details::{self, PackageDetails};
+use super::pkgbuild;
+use super::strategy::UpgradeStrategy;
+use super::Package;
+use std::path::PathBuf;
+use std::process::Command as StdCommand;
+use tokio::task;
+
+/// How to drive one AUR helper
+///
+/// Every helper-specific difference lives in this table, the backend itself is
+/// shared. Helpers are always run as the user; those that need root for the
+/// install step ask for it themselves.
+#[derive(Debug)]
+pub struct AurHelper {
+    /// Binary name, also used as the setting value
+    pub binary: &'static str,
+    /// Backend name shown in the popup
+    pub label: &'static str,
+    /// Arguments listing pending AUR updates, one "name old -> new" line each
+    check: &'static [&'static str],
+    /// Extra check argument that also reports VCS packages with new commits
+    check_devel: Option<&'static str>,
+    /// Token a check line must contain to be an AUR update (for helpers that list repository updates too)
+    aur_marker: Option<&'static str>,
+    /// Arguments for a full upgrade
+    upgrade: &'static [&'static str],
+    /// The upgrade only covers the AUR, so `pacman -Syu` runs first
+    upgrade_repo_first: bool,
+    /// Upgrade flag that also rebuilds VCS packages
+    upgrade_devel: Option<&'static str>,
+    /// Upgrade flag that leaves AUR packages alone (without one, plain pacman is used)
+    upgrade_skip_aur: Option<&'static str>,
+    /// Flag that skips confirmation prompts
+    noconfirm: &'static str,
+    /// Extra upgrade flags the user may add (entries ending in '=' take a value)
+    extra_flags: &'static [&'static str],
+    /// The helper passes pacman's own flags on to pacman, so they are allowed too
+    pacman_flags: bool,
+    /// Arguments printing AUR package info in `pacman -Si` style
+    info: &'static [&'static str],
+    /// Directory (relative to the home directory) with the helper's git clones
+    clone_cache: Option<&'static str>,
+}
+
+/// Supported helpers, in the order they are picked when the setting is "auto"
+pub const HELPERS: &[AurHelper] = &[
+    AurHelper {
+        binary: "paru",
+        label: "Pacman + AUR (paru)",
+        check: &["-Qua"],
+        check_devel: Some("--devel"),
+        aur_marker: None,
+        upgrade: &["-Syu"],
+        upgrade_repo_first: false,
+        upgrade_devel: Some("--devel"),
+        upgrade_skip_aur: Some("--repo"),
+        noconfirm: "--noconfirm",
+        extra_flags: &[
+            "--cleanafter",
+            "--nocleanafter",
+            "--batchinstall",
+            "--nobatchinstall",
+            "--sudoloop",
+            "--nosudoloop",
+            "--removemake",
+            "--noremovemake",
+            "--upgrademenu",
+            "--noupgrademenu",
+            "--combinedupgrade",
+            "--nocombinedupgrade",
+            "--skipreview",
+        ],
+        pacman_flags: true,
+        info: &["-Si", "--aur"],
+        clone_cache: Some(".cache/paru/clone"),
+    },
+    AurHelper {
+        binary: "yay",
+        label: "Pacman + AUR (yay)",
+        check: &["-Qua"],
+        check_devel: Some("--devel"),
+        aur_marker: None,
+        upgrade: &["-Syu"],
+        upgrade_repo_first: false,
+        upgrade_devel: Some("--devel"),
+        upgrade_skip_aur: Some("--repo"),
+        noconfirm: "--noconfirm",
+        extra_flags: &[
+            "--cleanafter",
+            "--nocleanafter",
+            "--batchinstall",
+            "--nobatchinstall",
+            "--sudoloop",
+            "--nosudoloop",
+            "--removemake",
+            "--noremovemake",
+            "--combinedupgrade",
+            "--nocombinedupgrade",
+            "--timeupdate",
+        ],
+        pacman_flags: true,
+        info: &["-Si", "--aur"],
+        clone_cache: Some(".cache/yay"),
+    },
+    AurHelper {
+        binary: "pikaur",
+        label: "Pacman + AUR (pikaur)",
+        check: &["-Qua"],
+        check_devel: Some("--devel"),
+        aur_marker: None,
+        upgrade: &["-Syu"],
+        upgrade_repo_first: false,
+        upgrade_devel: Some("--devel"),
+        upgrade_skip_aur: Some("--repo"),
+        noconfirm: "--noconfirm",
+        extra_flags: &["--noedit", "--nodiff", "--keepbuild", "--rebuild"],
+        pacman_flags: true,
+        info: &["-Si", "--aur"],
+        clone_cache: Some(".cache/pikaur/aur_repos"),
+    },
+    AurHelper {
+        binary: "aura",
+        label: "Pacman + AUR (aura)",
+        check: &["-Au", "--dryrun"],
+        check_devel: None,
+        aur_marker: None,
+        upgrade: &["-Au"],
+        upgrade_repo_first: true,
+        upgrade_devel: Some("--git"),
+        upgrade_skip_aur: None,
+        noconfirm: "--noconfirm",
+        extra_flags: &["--hotedit", "--diff"],
+        pacman_flags: false,
+        info: &["-Ai"],
+        clone_cache: None,
+    },
+    AurHelper {
+        binary: "trizen",
+        label: "Pacman + AUR (trizen)",
+        check: &["-Qua"],
+        check_devel: None,
+        aur_marker: None,
+        upgrade: &["-Syu"],
+        upgrade_repo_first: false,
+        upgrade_devel: Some("--devel"),
+        upgrade_skip_aur: None,
+        noconfirm: "--noconfirm",
+        extra_flags: &["--noedit", "--noinfo"],
+        pacman_flags: true,
+        info: &["-Si", "--aur"],
+        clone_cache: None,
+    },
+    AurHelper {
+        binary: "pamac",
+        label: "Pacman + AUR (pamac)",
+        check: &["checkupdates", "-a"],
+        check_devel: Some("--devel"),
+        aur_marker: Some("AUR"),
+        upgrade: &["upgrade", "-a"],
+        upgrade_repo_first: false,
+        upgrade_devel: Some("--devel"),
+        upgrade_skip_aur: None,
+        noconfirm: "--no-confirm",
+        extra_flags: &["--no-refresh", "--force-refresh", "--enable-downgrade"],
+        pacman_flags: false,
+        info: &["info", "-a"],
+        clone_cache: None,
+    },
+];
+
+impl AurHelper {
+    /// Look up a helper by binary name
+    pub fn find(name: &str) -> Option<&'static AurHelper> {
+        HELPERS.iter().find(|helper| helper.binary == name)
+    }
+
+    /// Check whether the helper is installed
+    pub fn is_installed(&self) -> bool {
+        StdCommand::new(self.binary).arg("--version").output().is_ok()
+    }
+
+    /// Build the upgrade command line (without the repository step of `upgrade_repo_first`)
+    ///
+    /// # Returns
+    /// * `Some(args)` - Helper command line
+    /// * `None` - AUR packages are skipped and the helper has no flag for that,
+    ///   so plain pacman should upgrade the repositories instead
+    pub fn upgrade_command(&self, devel: bool, skip_aur: bool, noconfirm: bool) -> Option<Vec<String>> {
+        let mut command = vec![self.binary.to_string()];
+        command.extend(self.upgrade.iter().map(|arg| arg.to_string()));
+
+        if skip_aur {
+            command.push(self.upgrade_skip_aur?.to_string());
+        } else if let Some(flag) = self.upgrade_devel.filter(|_| devel) {
+            command.push(flag.to_string());
+        }
+        if noconfirm {
+            command.push(self.noconfirm.to_string());
+        }
+        Some(command)
+    }
+
+    /// Pick the helper for the AUR helper setting
+    ///
+    /// "none" means plain pacman, a helper name uses that helper if it is
+    /// installed, and otherwise the first installed one from [`HELPERS`] is used.
+    pub fn select(preferred: &str) -> Option<&'static AurHelper> {
+        if preferred == "none" {
+            return None;
+        }
+        Self::find(preferred)
+            .filter(|helper| helper.is_installed())
+            .or_else(|| HELPERS.iter().find(|helper| helper.is_installed()))
+    }
+
+    /// Extra upgrade flags this helper accepts, on top of pacman's if `passes_pacman_flags`
+    pub fn allowed_flags(&self) -> &'static [&'static str] {
+        self.extra_flags
+    }
+
+    /// Whether pacman's own upgrade flags are allowed for this helper
+    pub fn passes_pacman_flags(&self) -> bool {
+        self.pacman_flags
+    }
+
+    /// Whether the AUR upgrade needs a `pacman -Syu` before it
+    pub fn upgrades_repositories_separately(&self) -> bool {
+        self.upgrade_repo_first
+    }
+
+    /// Directory with the helper's clones, if it keeps any
+    fn clone_dir(&self) -> Result<Option<PathBuf>, String> {
+        match self.clone_cache {
+            Some(cache) => Ok(Some(
+                dirs::home_dir().ok_or("Could not determine home directory")?.join(cache),
+            )),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Pacman + AUR backend driven by an [`AurHelper`] table entry
+#[derive(Clone)]
+pub struct AurHelperPackageManager {
+    pub helper: &'static AurHelper,
+}
+
+impl AurHelperPackageManager {
+    /// List pending AUR updates
+    ///
+    /// # Arguments
+    /// * `devel` - Also check VCS packages for new upstream commits, if the helper
+    ///   supports it. Only packages recorded in the helper's devel database are checked.
+    pub async fn check_updates(&self, devel: bool) -> Result<Vec<Package>, String> {
+        let helper = self.helper;
+        task::spawn_blocking(move || {
+            let mut command = StdCommand::new(helper.binary);
+            command.args(helper.check);
+            if let Some(flag) = helper.check_devel.filter(|_| devel) {
+                command.arg(flag);
+            }
+            let output = command
+                .output()
+                .map_err(|e| format!("Failed to run {}: {}", helper.binary, e))?;
+
+            // pamac checkupdates exits with 100 when it found updates
+            if !output.status.success() && output.status.code() != Some(100) {
+                return Ok(Vec::new());
+            }
+
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let packages = parse_helper_output(helper, &stdout);
+
+            Ok(packages)
+        })
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+    }
+
+    /// Launch the helper's upgrade in a terminal emulator
+    ///
+    /// Spawns the specified terminal with an upgrade command that handles both
+    /// official repository packages and AUR packages. Helpers that only upgrade
+    /// the AUR run after `pacman -Syu`.
+    ///
+    /// # Arguments
+    /// * `terminal` - Terminal emulator to use (e.g., "cosmic-term", "konsole")
+    /// * `strategy` - Configured upgrade options (subcommand, prompts, extra flags)
+    ///
+    /// # Returns
+    /// * `Ok(())` - Terminal process spawned successfully
+    /// * `Err(String)` - Failed to spawn terminal, or an extra flag is not allowed
+    pub async fn run_upgrade(&self, terminal: &str, strategy: &UpgradeStrategy) -> Result<(), String> {
+        let terminal = terminal.to_string();
+        let upgrade = strategy.aur_command(self.helper)?.join(" ");
+        task::spawn_blocking(move || {
+            // Use bash -c to chain the upgrade command with a prompt to keep terminal open
+            let command = format!("{}; echo ''; echo 'Press Enter to close...'; read", upgrade);
+            StdCommand::new(&terminal)
+                .args(["-e", "bash", "-c", &command])
+                .spawn()
+                .map_err(|e| format!("Failed to launch terminal '{}': {}", terminal, e))?;
+
+            Ok(())
+        })
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+    }
+
+    /// Show the AUR git history since the installed version
+    ///
+    /// Reads the package's clone in the helper's cache, or the applet's own clone
+    /// for helpers that keep none.
+    pub async fn changelog(&self, package: &Package) -> Result<Vec<ChangelogEntry>, String> {
+        let helper = self.helper;
+        let package = package.clone();
+        task::spawn_blocking(move || {
+            let base = pkgbuild::pkgbase(&package.name, &package.current_version);
+            let clone_dir = pkgbuild::clone_for(helper.clone_dir()?.as_deref(), &base)?;
+            changelog::aur_git_log(&clone_dir, &package.current_version)
+        })
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+    }
+
+    /// Diff the PKGBUILD and install scripts between the installed and the new version
+    ///
+    /// Uses the package's clone in the helper's cache when present.
+    pub async fn pkgbuild_diff(&self, package: &Package) -> Result<String, String> {
+        let helper = self.helper;
+        let package = package.clone();
+        task::spawn_blocking(move || pkgbuild::aur_diff(helper.clone_dir()?.as_deref(), &package))
+            .await
+            .map_err(|e| format!("Task join error: {}", e))?
+    }
+
+    /// Collect AUR metadata for the package detail page (e.g. `paru -Si --aur`)
+    pub async fn details(&self, package: &Package) -> Result<PackageDetails, String> {
+        let helper = self.helper;
+        let package = package.clone();
+        task::spawn_blocking(move || {
+            let new = StdCommand::new(helper.binary)
+                .args(helper.info)
+                .arg(&package.name)
+                .output()
+                .map_err(|e| format!("Failed to run {}: {}", helper.binary, e))?;
+            if !new.status.success() {
+                return Err(format!("No AUR information for {}", package.name));
+            }
+
+            let installed = StdCommand::new("pacman")
+                .args(["-Qi", &package.name])
+                .output()
+                .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
+                .unwrap_or_default();
+
+            Ok(details::from_pacman_info(
+                &String::from_utf8_lossy(&new.stdout),
+                &installed,
+            ))
+        })
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.helper.label
+    }
+
+    /// Refresh AUR metadata
+    ///
+    /// Every supported helper asks the AUR RPC for current versions on each
+    /// check, so there is no local AUR database to refresh. The helpers' `-Sy`
+    /// is deliberately not used: it would sync the system pacman database
+    /// without upgrading. The official repositories are refreshed into the
+    /// temporary checkupdates database by `PacmanPackageManager::refresh_cache` instead.
+    pub async fn refresh_cache(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Parse an AUR helper's update list into packages
+///
+/// Lines look like "name old -> new" (paru, yay, pikaur, trizen), "name : old => new"
+/// (aura) or "name old -> new AUR" (pamac). The arrow is located first, and the
+/// two fields before it and the one after it are taken.
+fn parse_helper_output(helper: &AurHelper, output: &str) -> Vec<Package> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().filter(|part| *part != ":").collect();
+
+            // Headers and progress messages (aura, pamac) have no arrow
+            let arrow = parts.iter().position(|part| *part == "->" || *part == "=>")?;
+
+            // Validate we have all required fields
+            if arrow < 2 || parts.len() < arrow + 2 {
+                eprintln!("Warning: Skipping malformed {} line (insufficient fields): {}", helper.binary, line);
+                return None;
+            }
+
+            // Helpers that list repository updates too mark the AUR ones
+            if let Some(marker) = helper.aur_marker {
+                if !parts[arrow + 2..].contains(&marker) {
+                    return None;
+                }
+            }
+
+            Some(Package {
+                name: parts[arrow - 2].to_string(),
+                current_version: parts[arrow - 1].to_string(),
+                new_version: parts[arrow + 1].to_string(),
+                is_aur: true,
+                ..Default::default()
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,202 @@
+use super::Package;
+use crate::utils;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::process::Command as StdCommand;
+use tokio::task;
+
+/// Default AUR web and git server
+pub const DEFAULT_AUR_URL: &str = "https://aur.archlinux.org";
+
+/// Names per RPC request, keeps the query string well below URL length limits
+const MAX_NAMES_PER_REQUEST: usize = 100;
+
+/// Response of the AUR RPC (v5) `info` endpoint
+#[derive(Debug, Deserialize)]
+struct RpcResponse {
+    #[serde(default)]
+    results: Vec<RpcPackage>,
+    /// Set instead of results when the request was rejected
+    #[serde(default)]
+    error: Option<String>,
+}
+
+/// One package of an `info` response, only the fields the applet uses
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RpcPackage {
+    name: String,
+    /// None when the package is orphaned
+    #[serde(default)]
+    maintainer: Option<String>,
+    #[serde(default)]
+    num_votes: u32,
+    /// Unix timestamp of the last package update
+    #[serde(default)]
+    last_modified: i64,
+    /// Unix timestamp of the out-of-date flag, None when not flagged
+    #[serde(default)]
+    out_of_date: Option<i64>,
+}
+
+/// Why an installed foreign package needs attention
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ForeignIssue {
+    /// Not in the AUR (deleted, merged, renamed or never published), so it never gets updates
+    Deleted,
+    /// In the AUR without a maintainer
+    Orphaned,
+    /// Flagged out of date at the given Unix time
+    OutOfDate(i64),
+}
+
+impl ForeignIssue {
+    /// Short description shown in the popup
+    pub fn describe(&self) -> String {
+        match self {
+            ForeignIssue::Deleted => "not in the AUR (deleted or renamed)".to_string(),
+            ForeignIssue::Orphaned => "orphaned".to_string(),
+            ForeignIssue::OutOfDate(since) => format!("flagged out of date since {}", format_date(*since)),
+        }
+    }
+}
+
+/// An installed foreign package (`pacman -Qm`) with a maintenance problem
+#[derive(Debug, Clone)]
+pub struct ForeignPackage {
+    pub name: String,
+    pub version: String,
+    pub issue: ForeignIssue,
+}
+
+/// Attach AUR metadata (maintainer, votes, last change, out-of-date flag) to AUR updates
+///
+/// Errors are logged and leave the packages unchanged, like the security annotation.
+///
+/// # Arguments
+/// * `packages` - Pending updates, only those with `is_aur` are looked up
+/// * `base_url` - AUR base URL, e.g. "https://aur.archlinux.org" or a local stand-in
+pub async fn annotate(packages: Vec<Package>, base_url: String) -> Vec<Package> {
+    // A panicking lookup must not drop the updates themselves
+    let unchanged = packages.clone();
+    task::spawn_blocking(move || {
+        let mut packages = packages;
+        let names: Vec<String> = packages.iter().filter(|p| p.is_aur).map(|p| p.name.clone()).collect();
+        if names.is_empty() {
+            return packages;
+        }
+
+        match query_info(&base_url, &names) {
+            Ok(info) => {
+                for package in packages.iter_mut().filter(|p| p.is_aur) {
+                    if let Some(info) = info.get(&package.name) {
+                        package.aur_maintainer = info.maintainer.clone().unwrap_or_default();
+                        package.aur_votes = info.num_votes;
+                        package.aur_last_modified = info.last_modified;
+                        package.aur_out_of_date = info.out_of_date;
+                    }
+                }
+            }
+            Err(e) => eprintln!("Warning: Could not query the AUR: {}", e),
+        }
+        packages
+    })
+    .await
+    .unwrap_or_else(|e| {
+        eprintln!("Warning: AUR metadata lookup failed: {}", e);
+        unchanged
+    })
+}
+
+/// Find installed foreign packages that are deleted, orphaned or flagged out of date in the AUR
+///
+/// These never appear in `-Qua` when nothing new is published, so they are
+/// checked separately against every package `pacman -Qm` lists.
+///
+/// # Arguments
+/// * `base_url` - AUR base URL
+pub async fn audit_foreign(base_url: String) -> Result<Vec<ForeignPackage>, String> {
+    task::spawn_blocking(move || {
+        let output = StdCommand::new("pacman")
+            .arg("-Qm")
+            .output()
+            .map_err(|e| format!("Failed to run pacman: {}", e))?;
+
+        // pacman -Qm exits with 1 when there are no foreign packages
+        let installed: Vec<(String, String)> = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                let (name, version) = line.trim().split_once(' ')?;
+                Some((name.to_string(), version.to_string()))
+            })
+            .collect();
+        if installed.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let names: Vec<String> = installed.iter().map(|(name, _)| name.clone()).collect();
+        let info = query_info(&base_url, &names)?;
+
+        Ok(installed
+            .into_iter()
+            .filter_map(|(name, version)| {
+                let issue = match info.get(&name) {
+                    None => ForeignIssue::Deleted,
+                    Some(package) if package.maintainer.is_none() => ForeignIssue::Orphaned,
+                    Some(RpcPackage {
+                        out_of_date: Some(since),
+                        ..
+                    }) => ForeignIssue::OutOfDate(*since),
+                    Some(_) => return None,
+                };
+                Some(ForeignPackage { name, version, issue })
+            })
+            .collect())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Query the RPC `info` endpoint for a list of package names
+fn query_info(base_url: &str, names: &[String]) -> Result<HashMap<String, RpcPackage>, String> {
+    let mut packages = HashMap::new();
+
+    for chunk in names.chunks(MAX_NAMES_PER_REQUEST) {
+        let args: Vec<String> = chunk
+            .iter()
+            .map(|name| format!("arg%5B%5D={}", url_encode(name)))
+            .collect();
+        let url = format!("{}/rpc/?v=5&type=info&{}", base_url.trim_end_matches('/'), args.join("&"));
+
+        let json = utils::fetch_url(&url)?;
+        let response: RpcResponse =
+            serde_json::from_str(&json).map_err(|e| format!("Invalid AUR RPC response: {}", e))?;
+        if let Some(error) = response.error {
+            return Err(format!("AUR RPC error: {}", error));
+        }
+
+        packages.extend(response.results.into_iter().map(|package| (package.name.clone(), package)));
+    }
+
+    Ok(packages)
+}
+
+/// Percent-encode a package name for a query string
+///
+/// Package names may contain '+', '@' and '.', of which only '.' is safe as is.
+fn url_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Format a Unix timestamp as a local date
+pub fn format_date(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|date| date.with_timezone(&chrono::Local).format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
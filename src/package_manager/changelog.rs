@@ -0,0 +1,156 @@
+use std::path::Path;
+use std::process::Command as StdCommand;
+
+/// Maximum number of AUR git commits to show when no version marker is found
+const MAX_GIT_ENTRIES: usize = 20;
+
+/// Maximum number of changelog entries to show when the installed version has no entry
+const MAX_ENTRIES: usize = 20;
+
+/// A single changelog entry (one Debian upload, one AUR commit, ...)
+#[derive(Debug, Clone)]
+pub struct ChangelogEntry {
+    /// Version the entry belongs to (may be empty for free-form changelogs)
+    pub version: String,
+    /// Date as printed by the source
+    pub date: String,
+    /// Entry body
+    pub text: String,
+}
+
+/// Parse a Debian changelog (debian/changelog format)
+///
+/// Entries start with a header line such as
+/// "firefox (121.0+build1-0ubuntu0.22.04.1) jammy; urgency=medium"
+/// and end with a trailer line " -- Maintainer <email>  Mon, 18 Dec 2023 12:00:00 +0000".
+pub fn parse_debian(text: &str) -> Vec<ChangelogEntry> {
+    let mut entries = Vec::new();
+    let mut current: Option<ChangelogEntry> = None;
+
+    for line in text.lines() {
+        let is_header = !line.starts_with(' ') && !line.trim().is_empty() && line.contains(" (");
+
+        if is_header {
+            if let Some(entry) = current.take() {
+                entries.push(entry);
+            }
+            let version = line
+                .split_once(" (")
+                .and_then(|(_, rest)| rest.split_once(')'))
+                .map(|(version, _)| version.to_string())
+                .unwrap_or_default();
+            current = Some(ChangelogEntry {
+                version,
+                date: String::new(),
+                text: String::new(),
+            });
+        } else if let Some(entry) = current.as_mut() {
+            if let Some(trailer) = line.strip_prefix(" -- ") {
+                // Date follows the maintainer after two spaces
+                entry.date = trailer
+                    .split_once("  ")
+                    .map(|(_, date)| date.trim().to_string())
+                    .unwrap_or_default();
+            } else if !line.trim().is_empty() {
+                entry.text.push_str(line.trim_end());
+                entry.text.push('\n');
+            }
+        }
+    }
+
+    if let Some(entry) = current.take() {
+        entries.push(entry);
+    }
+
+    entries
+}
+
+/// Keep only the entries between the installed and the new version
+///
+/// Changelogs list the newest entry first, so everything above the new
+/// version's entry is skipped, and collection stops at the installed version's
+/// entry. If the new version has no entry, collection starts at the top. If
+/// the installed version has no entry, only the first `MAX_ENTRIES` entries are
+/// kept, followed by a note that older entries were left out.
+pub fn cut_to_range(entries: Vec<ChangelogEntry>, current_version: &str, new_version: &str) -> Vec<ChangelogEntry> {
+    let start = entries
+        .iter()
+        .position(|entry| entry.version == new_version)
+        .unwrap_or(0);
+
+    let found = entries.iter().skip(start).any(|entry| entry.version == current_version);
+    let mut range: Vec<ChangelogEntry> = entries
+        .into_iter()
+        .skip(start)
+        .take_while(|entry| entry.version != current_version)
+        .collect();
+
+    if !found && range.len() > MAX_ENTRIES {
+        let omitted = range.len() - MAX_ENTRIES;
+        range.truncate(MAX_ENTRIES);
+        range.push(ChangelogEntry {
+            version: "…".to_string(),
+            date: String::new(),
+            text: format!("{} more entries not shown", omitted),
+        });
+    }
+
+    range
+}
+
+/// Read the git history of an AUR package from an AUR helper's clone cache
+///
+/// Commits are listed newest first and cut at the first commit that mentions the
+/// installed version (without pkgrel), since AUR commit subjects usually name the
+/// version they bump to.
+///
+/// # Arguments
+/// * `clone_dir` - The package's git clone (e.g. `~/.cache/paru/clone/<pkg>`)
+/// * `current_version` - Installed version, used to find where to stop
+pub fn aur_git_log(clone_dir: &Path, current_version: &str) -> Result<Vec<ChangelogEntry>, String> {
+    if !clone_dir.join(".git").exists() {
+        return Err(format!("No clone found at {}", clone_dir.display()));
+    }
+
+    // Make sure the clone knows about the new version
+    let _ = StdCommand::new("git")
+        .arg("-C")
+        .arg(clone_dir)
+        .args(["fetch", "--quiet", "origin"])
+        .output();
+
+    let output = StdCommand::new("git")
+        .arg("-C")
+        .arg(clone_dir)
+        .args(["log", "--date=short", "--format=%h%x09%ad%x09%s", "origin/HEAD"])
+        .output()
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("git log failed: {}", stderr));
+    }
+
+    let pkgver = current_version
+        .rsplit_once('-')
+        .map(|(pkgver, _pkgrel)| pkgver)
+        .unwrap_or(current_version);
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\t');
+            let hash = fields.next()?;
+            let date = fields.next()?;
+            let subject = fields.next()?;
+            Some(ChangelogEntry {
+                version: hash.to_string(),
+                date: date.to_string(),
+                text: subject.to_string(),
+            })
+        })
+        .take_while(|entry| !entry.text.contains(pkgver))
+        .take(MAX_GIT_ENTRIES)
+        .collect())
+}
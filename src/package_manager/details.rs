@@ -0,0 +1,187 @@
+use super::alpm_db::DbPackage;
+use crate::utils;
+
+/// Metadata shown on the package detail page
+#[derive(Debug, Clone, Default)]
+pub struct PackageDetails {
+    pub description: String,
+    pub repository: String,
+    pub url: String,
+    pub licenses: String,
+    pub packager: String,
+    pub build_date: String,
+    /// Size of the package file, empty if unknown
+    pub download_size: String,
+    /// Size of the new version once installed, empty if unknown
+    pub installed_size: String,
+    /// Dependencies the new version needs that the installed one did not
+    pub added_dependencies: Vec<String>,
+    /// Dependencies the installed version had that the new one dropped
+    pub removed_dependencies: Vec<String>,
+}
+
+impl PackageDetails {
+    /// Fill in the dependency changes between two dependency lists
+    pub fn set_dependency_changes(&mut self, installed: &[String], new: &[String]) {
+        self.added_dependencies = new
+            .iter()
+            .filter(|dep| !installed.contains(dep))
+            .cloned()
+            .collect();
+        self.removed_dependencies = installed
+            .iter()
+            .filter(|dep| !new.contains(dep))
+            .cloned()
+            .collect();
+    }
+}
+
+/// Build details from pacman-style `-Si` (new version) and `-Qi` (installed) output
+///
+/// AUR helpers print "Maintainer" and "Last Modified" instead of "Packager" and
+/// "Build Date", so those are used as fallbacks.
+pub fn from_pacman_info(new_output: &str, installed_output: &str) -> PackageDetails {
+    let new = parse_pacman_info(new_output);
+    let installed = parse_pacman_info(installed_output);
+
+    let or_else = |primary: &str, fallback: &str| {
+        let value = field(&new, primary);
+        if value.is_empty() {
+            field(&new, fallback).to_string()
+        } else {
+            value.to_string()
+        }
+    };
+
+    let mut details = PackageDetails {
+        description: field(&new, "Description").to_string(),
+        repository: field(&new, "Repository").to_string(),
+        url: field(&new, "URL").to_string(),
+        licenses: field(&new, "Licenses").to_string(),
+        packager: or_else("Packager", "Maintainer"),
+        build_date: or_else("Build Date", "Last Modified"),
+        ..Default::default()
+    };
+    details.set_dependency_changes(
+        &pacman_dependencies(field(&installed, "Depends On")),
+        &pacman_dependencies(field(&new, "Depends On")),
+    );
+    details
+}
+
+/// Build details from pacman database entries for the new and the installed version
+pub fn from_pacman_db(new: &DbPackage, installed: Option<&DbPackage>) -> PackageDetails {
+    let dependencies = |package: &DbPackage| pacman_dependencies(&package.depends.join(" "));
+    let size = |bytes: u64| if bytes > 0 { utils::format_size(bytes) } else { String::new() };
+
+    let mut details = PackageDetails {
+        description: new.description.clone(),
+        repository: new.repository.clone(),
+        url: new.url.clone(),
+        licenses: new.licenses.join(" "),
+        packager: new.packager.clone(),
+        build_date: chrono::DateTime::from_timestamp(new.build_date, 0)
+            .filter(|_| new.build_date > 0)
+            .map(|date| date.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default(),
+        download_size: size(new.download_size),
+        installed_size: size(new.installed_size),
+        ..Default::default()
+    };
+    details.set_dependency_changes(&installed.map(dependencies).unwrap_or_default(), &dependencies(new));
+    details
+}
+
+/// Parse pacman's `-Si`/`-Qi` output (also used by paru and yay)
+///
+/// Lines look like "Depends On      : glibc  zlib". Values that continue on the
+/// next line are indented, and "None" marks an empty field.
+pub fn parse_pacman_info(output: &str) -> Vec<(String, String)> {
+    let mut fields: Vec<(String, String)> = Vec::new();
+
+    for line in output.lines() {
+        match line.split_once(" : ") {
+            Some((key, value)) if !line.starts_with(' ') => {
+                fields.push((key.trim().to_string(), value.trim().to_string()));
+            }
+            _ => {
+                // Continuation of the previous value
+                if let Some((_, value)) = fields.last_mut() {
+                    if !line.trim().is_empty() {
+                        value.push(' ');
+                        value.push_str(line.trim());
+                    }
+                }
+            }
+        }
+    }
+
+    fields
+}
+
+/// Parse a Debian control stanza as printed by `apt-cache show`
+///
+/// Only the first stanza is read. Continuation lines start with a space, and a
+/// lone "." stands for an empty line in long descriptions.
+pub fn parse_debian_control(output: &str) -> Vec<(String, String)> {
+    let mut fields: Vec<(String, String)> = Vec::new();
+
+    for line in output.lines() {
+        if line.trim().is_empty() {
+            break;
+        }
+        if line.starts_with(' ') || line.starts_with('\t') {
+            if let Some((_, value)) = fields.last_mut() {
+                value.push('\n');
+                let continuation = line.trim();
+                if continuation != "." {
+                    value.push_str(continuation);
+                }
+            }
+        } else if let Some((key, value)) = line.split_once(':') {
+            fields.push((key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+
+    fields
+}
+
+/// Look up a field by name
+pub fn field<'a>(fields: &'a [(String, String)], key: &str) -> &'a str {
+    fields
+        .iter()
+        .find(|(k, _)| k == key)
+        .map(|(_, v)| v.as_str())
+        .filter(|v| *v != "None")
+        .unwrap_or("")
+}
+
+/// Split a pacman dependency field into names without version constraints
+pub fn pacman_dependencies(value: &str) -> Vec<String> {
+    value
+        .split_whitespace()
+        .filter(|dep| *dep != "None")
+        .map(|dep| {
+            dep.split(['<', '>', '='])
+                .next()
+                .unwrap_or(dep)
+                .to_string()
+        })
+        .collect()
+}
+
+/// Split a Debian Depends field into names without version constraints
+///
+/// Alternatives stay together ("zlib1g | zlib-ng") so they compare as one dependency.
+pub fn debian_dependencies(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|dep| {
+            dep.split('|')
+                .map(|alt| alt.split('(').next().unwrap_or(alt).trim())
+                .collect::<Vec<_>>()
+                .join(" | ")
+        })
+        .filter(|dep| !dep.is_empty())
+        .collect()
+}
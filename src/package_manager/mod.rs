@@ -1,30 +1,73 @@
+pub mod alpm_db;
 pub mod apt;
+pub mod apt_db;
+pub mod arch_security;
+pub mod aur_helper;
+pub mod aur_rpc;
+pub mod changelog;
+pub mod details;
 pub mod pacman;
-pub mod paru;
-pub mod yay;
+pub mod pacman_conf;
+pub mod pkgbuild;
+pub mod repo_health;
+pub mod sources;
+pub mod strategy;
+pub mod version;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct Package {
     pub name: String,
     pub current_version: String,
     pub new_version: String,
     pub is_aur: bool, // Track if this is from AUR
+    pub is_security: bool, // Classified as a security update by the backend
+    pub repository: String, // Repository/suite the new version comes from (may be empty)
+    pub cves: Vec<String>, // CVE IDs fixed by this update (Arch security advisories)
+    pub severity: String, // Highest advisory severity among those CVEs (may be empty)
+    pub bump: version::Bump, // How far the new version is from the installed one
+    pub hold: String, // Native hold keeping the package back (e.g. "apt-mark hold"), empty if none
+    pub phased: bool, // APT is still phasing this update in, so `apt upgrade` skips it
+    pub phased_percentage: Option<u32>, // Share of machines the phased update is offered to
+    pub kept_back: bool, // `apt upgrade` keeps this back (needs new packages or removals)
+    pub kept_back_reason: String, // What installing it would change, e.g. "needs new packages: ..."
+    pub is_vcs: bool, // AUR VCS package (-git, -svn, ...) reported by the devel check
+    pub aur_maintainer: String, // AUR maintainer, empty if unknown or orphaned
+    pub aur_votes: u32, // AUR vote count
+    pub aur_last_modified: i64, // Last change on the AUR (Unix seconds), 0 if unknown
+    pub aur_out_of_date: Option<i64>, // When the AUR package was flagged out of date
+    pub origin: String, // Origin of the archive the new version comes from (APT, e.g. "Ubuntu"), may be empty
+    pub download_size: u64, // Size of the package file in bytes, 0 if unknown
+    pub installed_size: u64, // Size of the new version once installed in bytes, 0 if unknown
+}
+
+/// Name suffixes of AUR packages built from the latest upstream commit
+const VCS_SUFFIXES: &[&str] = &["-git", "-svn", "-hg", "-bzr", "-darcs", "-fossil", "-cvs"];
+
+/// Check whether an AUR update comes from a VCS package
+///
+/// paru and yay report a new upstream commit as "latest-commit" instead of a
+/// version, so that counts as well as the usual naming convention.
+pub fn is_vcs_package(package: &Package) -> bool {
+    package.new_version == "latest-commit" || VCS_SUFFIXES.iter().any(|suffix| package.name.ends_with(suffix))
 }
 
 #[derive(Clone)]
 pub enum PackageManager {
     Apt(apt::AptPackageManager),
     Pacman(pacman::PacmanPackageManager),
-    CombinedParu(pacman::PacmanPackageManager, paru::ParuPackageManager), // Pacman + paru
-    CombinedYay(pacman::PacmanPackageManager, yay::YayPackageManager),    // Pacman + yay
+    CombinedAur(pacman::PacmanPackageManager, aur_helper::AurHelperPackageManager), // Pacman + an AUR helper
 }
 
 impl PackageManager {
-    pub async fn check_updates(&self) -> Result<Vec<Package>, String> {
-        match self {
-            PackageManager::Apt(pm) => pm.check_updates().await,
-            PackageManager::Pacman(pm) => pm.check_updates().await,
-            PackageManager::CombinedParu(pacman, paru) => {
+    /// Check all sources for pending updates
+    ///
+    /// # Arguments
+    /// * `vcs` - Also ask the AUR helper for new upstream commits of VCS packages (`--devel`)
+    pub async fn check_updates(&self, vcs: bool) -> Result<Vec<Package>, String> {
+        let mut packages = match self {
+            PackageManager::Apt(pm) => pm.check_updates().await?,
+            PackageManager::Pacman(pm) => pm.check_updates().await?,
+            PackageManager::CombinedAur(pacman, helper) => {
                 // Get both official repo and AUR updates
                 let mut all_packages = Vec::new();
 
@@ -36,28 +79,69 @@ impl PackageManager {
                 all_packages.extend(official);
 
                 // AUR packages
-                let aur = paru.check_updates().await?;
+                let mut aur = helper.check_updates(vcs).await?;
+                if vcs {
+                    for pkg in &mut aur {
+                        pkg.is_vcs = is_vcs_package(pkg);
+                    }
+                }
                 all_packages.extend(aur);
 
-                Ok(all_packages)
+                all_packages
             }
-            PackageManager::CombinedYay(pacman, yay) => {
-                // Get both official repo and AUR updates
-                let mut all_packages = Vec::new();
+        };
 
-                // Official repos
-                let mut official = pacman.check_updates().await?;
-                for pkg in &mut official {
-                    pkg.is_aur = false;
-                }
-                all_packages.extend(official);
+        let scheme = self.version_scheme();
+        let holds = self.holds().await;
+        for package in &mut packages {
+            // "latest-commit" is not a version, so VCS updates stay unclassified
+            if !package.is_vcs {
+                package.bump = version::classify(scheme, &package.current_version, &package.new_version);
+            }
+            if let Some((_, source)) = holds
+                .iter()
+                .find(|(pattern, _)| crate::utils::glob_match(pattern, &package.name))
+            {
+                package.hold = source.clone();
+            }
+        }
 
-                // AUR packages
-                let aur = yay.check_updates().await?;
-                all_packages.extend(aur);
+        Ok(packages)
+    }
 
-                Ok(all_packages)
-            }
+    /// Read the distro-level holds as (name or glob pattern, source) pairs
+    ///
+    /// AUR helpers honour pacman's `IgnorePkg`, so the combined backends use pacman's list.
+    pub async fn holds(&self) -> Vec<(String, String)> {
+        match self {
+            PackageManager::Apt(pm) => pm.holds().await,
+            PackageManager::Pacman(pm) => pm.holds().await,
+            PackageManager::CombinedAur(pacman, _helper) => pacman.holds().await,
+        }
+    }
+
+    /// Hold or release a package with the distro's own mechanism (through pkexec)
+    pub async fn set_hold(&self, name: String, hold: bool) -> Result<(), String> {
+        match self {
+            PackageManager::Apt(pm) => pm.set_hold(name, hold).await,
+            PackageManager::Pacman(pm) => pm.set_hold(name, hold).await,
+            PackageManager::CombinedAur(pacman, _helper) => pacman.set_hold(name, hold).await,
+        }
+    }
+
+    /// List the configured package sources, with the Flatpak remotes
+    pub async fn sources(&self) -> Vec<sources::Source> {
+        match self {
+            PackageManager::Apt(_) => sources::apt_sources().await,
+            PackageManager::Pacman(_) | PackageManager::CombinedAur(_, _) => sources::pacman_sources().await,
+        }
+    }
+
+    /// Version format of the backend's packages
+    pub fn version_scheme(&self) -> version::Scheme {
+        match self {
+            PackageManager::Apt(_) => version::Scheme::Debian,
+            PackageManager::Pacman(_) | PackageManager::CombinedAur(_, _) => version::Scheme::Pacman,
         }
     }
 
@@ -68,31 +152,133 @@ impl PackageManager {
     ///
     /// # Arguments
     /// * `terminal` - Name of the terminal emulator to use (e.g., "cosmic-term", "konsole")
+    /// * `strategy` - Configured upgrade options
     ///
     /// # Returns
     /// * `Ok(())` - Terminal launched successfully
     /// * `Err(String)` - Failed to launch terminal with error message
-    pub async fn run_upgrade(&self, terminal: &str) -> Result<(), String> {
+    pub async fn run_upgrade(&self, terminal: &str, strategy: &strategy::UpgradeStrategy) -> Result<(), String> {
+        match self {
+            PackageManager::Apt(pm) => pm.run_upgrade(terminal, strategy).await,
+            PackageManager::Pacman(pm) => pm.run_upgrade(terminal, strategy).await,
+            PackageManager::CombinedAur(_pacman, helper) => {
+                // Use the helper for upgrade since it handles both official + AUR
+                helper.run_upgrade(terminal, strategy).await
+            }
+        }
+    }
+
+    /// Command line the Upgrade button runs with the given strategy (for the settings preview)
+    pub fn upgrade_command(&self, strategy: &strategy::UpgradeStrategy) -> Result<String, String> {
+        let command = match self {
+            PackageManager::Apt(_) => strategy.apt_command()?,
+            PackageManager::Pacman(_) => strategy.pacman_command()?,
+            PackageManager::CombinedAur(_, helper) => strategy.aur_command(helper.helper)?,
+        };
+        Ok(command.join(" "))
+    }
+
+    /// True when an AUR helper handles upgrades
+    pub fn has_aur_helper(&self) -> bool {
+        matches!(self, PackageManager::CombinedAur(_, _))
+    }
+
+    /// Launch an upgrade for packages the normal upgrade keeps back (APT only)
+    ///
+    /// # Arguments
+    /// * `terminal` - Terminal emulator to use
+    /// * `name` - Package to install, or None for a full upgrade
+    pub async fn run_kept_back_upgrade(&self, terminal: &str, name: Option<String>) -> Result<(), String> {
+        match self {
+            PackageManager::Apt(pm) => pm.run_kept_back_upgrade(terminal, name).await,
+            _ => Err("Only APT keeps packages back".to_string()),
+        }
+    }
+
+    /// Upgrade only the named packages without any interaction (requires root)
+    ///
+    /// Used by the privileged helper for unattended security updates.
+    pub async fn upgrade_packages(&self, names: Vec<String>) -> Result<(), String> {
+        match self {
+            PackageManager::Apt(pm) => pm.upgrade_packages(names).await,
+            PackageManager::Pacman(pm) => pm.upgrade_packages(names).await,
+            PackageManager::CombinedAur(pacman, _helper) => pacman.upgrade_packages(names).await,
+        }
+    }
+
+    /// Fetch the changelog entries between a package's installed and new version
+    pub async fn changelog(&self, package: &Package) -> Result<Vec<changelog::ChangelogEntry>, String> {
         match self {
-            PackageManager::Apt(pm) => pm.run_upgrade(terminal).await,
-            PackageManager::Pacman(pm) => pm.run_upgrade(terminal).await,
-            PackageManager::CombinedParu(_pacman, paru) => {
-                // Use paru for upgrade since it handles both official + AUR
-                paru.run_upgrade(terminal).await
+            PackageManager::Apt(pm) => pm.changelog(package).await,
+            PackageManager::Pacman(pm) => pm.changelog(package).await,
+            PackageManager::CombinedAur(pacman, helper) => {
+                if package.is_aur {
+                    helper.changelog(package).await
+                } else {
+                    pacman.changelog(package).await
+                }
             }
-            PackageManager::CombinedYay(_pacman, yay) => {
-                // Use yay for upgrade since it handles both official + AUR
-                yay.run_upgrade(terminal).await
+        }
+    }
+
+    /// Diff the PKGBUILD and install scripts of a pending AUR update
+    ///
+    /// Only AUR packages have a PKGBUILD to review before upgrading.
+    pub async fn pkgbuild_diff(&self, package: &Package) -> Result<String, String> {
+        match self {
+            PackageManager::CombinedAur(_pacman, helper) if package.is_aur => helper.pkgbuild_diff(package).await,
+            _ => Err("Only AUR packages have a PKGBUILD to review".to_string()),
+        }
+    }
+
+    /// Collect metadata for the package detail page
+    pub async fn details(&self, package: &Package) -> Result<details::PackageDetails, String> {
+        match self {
+            PackageManager::Apt(pm) => pm.details(package).await,
+            PackageManager::Pacman(pm) => pm.details(package).await,
+            PackageManager::CombinedAur(pacman, helper) => {
+                if package.is_aur {
+                    helper.details(package).await
+                } else {
+                    pacman.details(package).await
+                }
             }
         }
     }
 
+    /// Download pending updates into the package cache without installing them
+    ///
+    /// AUR packages are built at install time, so the combined backends only
+    /// pre-download the official repository packages.
+    pub async fn download_updates(&self) -> Result<(), String> {
+        match self {
+            PackageManager::Apt(pm) => pm.download_updates().await,
+            PackageManager::Pacman(pm) => pm.download_updates().await,
+            PackageManager::CombinedAur(pacman, _helper) => pacman.download_updates().await,
+        }
+    }
+
     pub async fn is_running(&self) -> bool {
         match self {
             PackageManager::Apt(pm) => pm.is_running().await,
             PackageManager::Pacman(pm) => pm.is_running().await,
-            PackageManager::CombinedParu(pacman, _paru) => pacman.is_running().await,
-            PackageManager::CombinedYay(pacman, _yay) => pacman.is_running().await,
+            PackageManager::CombinedAur(pacman, _helper) => pacman.is_running().await,
+        }
+    }
+
+    /// True for the pacman-based backends (with or without an AUR helper)
+    pub fn is_arch(&self) -> bool {
+        !matches!(self, PackageManager::Apt(_))
+    }
+
+    /// Backend name understood by the offline update helper
+    ///
+    /// The combined AUR backends map to "pacman": AUR packages cannot be built as
+    /// root during an offline update, so only official packages are installed.
+    pub fn offline_backend(&self) -> &'static str {
+        match self {
+            PackageManager::Apt(_) => "apt",
+            PackageManager::Pacman(_) | PackageManager::CombinedAur(_, _) => "pacman",
         }
     }
 
@@ -100,32 +286,65 @@ impl PackageManager {
         match self {
             PackageManager::Apt(pm) => pm.name(),
             PackageManager::Pacman(pm) => pm.name(),
-            PackageManager::CombinedParu(_pacman, paru) => paru.name(),
-            PackageManager::CombinedYay(_pacman, yay) => yay.name(),
+            PackageManager::CombinedAur(_pacman, helper) => helper.name(),
+        }
+    }
+
+    /// Number of installed packages the system sync database is already ahead of
+    ///
+    /// Non-zero on Arch means the real database was synced without upgrading.
+    /// Always 0 for APT, where refreshing the lists is the normal workflow.
+    pub async fn out_of_sync_count(&self) -> usize {
+        match self {
+            PackageManager::Apt(_) => 0,
+            PackageManager::Pacman(pacman) | PackageManager::CombinedAur(pacman, _) => {
+                pacman.out_of_sync_count().await
+            }
+        }
+    }
+
+    /// Sync what the update check reads, if that needs no privileges
+    ///
+    /// On Arch this syncs the temporary databases before every check. APT's
+    /// lists need root, so they are only refreshed by [`Self::refresh_cache`].
+    ///
+    /// # Returns
+    /// * `Ok(Some(issues))` - Repository problems found while syncing
+    /// * `Ok(None)` - Nothing was synced, the last report still stands
+    pub async fn sync_databases(&self) -> Result<Option<Vec<repo_health::RepositoryIssue>>, String> {
+        match self {
+            PackageManager::Apt(_) => Ok(None),
+            PackageManager::Pacman(pacman) | PackageManager::CombinedAur(pacman, _) => {
+                pacman.sync_databases().await.map(Some)
+            }
         }
     }
 
-    pub async fn refresh_cache(&self) -> Result<(), String> {
+    /// Refresh the package lists after an upgrade
+    ///
+    /// # Returns
+    /// Problems reported per repository (expired keys, missing Release files, ...)
+    pub async fn refresh_cache(&self) -> Result<Vec<repo_health::RepositoryIssue>, String> {
         match self {
             PackageManager::Apt(pm) => pm.refresh_cache().await,
             PackageManager::Pacman(pm) => pm.refresh_cache().await,
-            PackageManager::CombinedParu(pacman, paru) => {
-                // Refresh both
-                pacman.refresh_cache().await?;
-                paru.refresh_cache().await?;
-                Ok(())
-            }
-            PackageManager::CombinedYay(pacman, yay) => {
-                // Refresh both
-                pacman.refresh_cache().await?;
-                yay.refresh_cache().await?;
-                Ok(())
+            PackageManager::CombinedAur(pacman, helper) => {
+                // Official repositories go into the temporary database, never the system one
+                let issues = pacman.refresh_cache().await?;
+                helper.refresh_cache().await?;
+                Ok(issues)
             }
         }
     }
 }
 
-pub fn detect_package_manager() -> Option<PackageManager> {
+/// Detect the system's package manager
+///
+/// # Arguments
+/// * `preferred_helper` - AUR helper to use on Arch: "auto" picks the first
+///   installed one from [`aur_helper::HELPERS`], "none" uses plain pacman, and a
+///   helper name uses that helper (falling back to "auto" if it is not installed)
+pub fn detect_package_manager(preferred_helper: &str) -> Option<PackageManager> {
     if std::process::Command::new("apt")
         .arg("--version")
         .output()
@@ -139,28 +358,13 @@ pub fn detect_package_manager() -> Option<PackageManager> {
         .output()
         .is_ok()
     {
-        // Check for AUR helpers in order of preference: paru > yay
-        if std::process::Command::new("paru")
-            .arg("--version")
-            .output()
-            .is_ok()
-        {
-            return Some(PackageManager::CombinedParu(
-                pacman::PacmanPackageManager,
-                paru::ParuPackageManager,
-            ));
-        } else if std::process::Command::new("yay")
-            .arg("--version")
-            .output()
-            .is_ok()
-        {
-            return Some(PackageManager::CombinedYay(
+        return Some(match aur_helper::AurHelper::select(preferred_helper) {
+            Some(helper) => PackageManager::CombinedAur(
                 pacman::PacmanPackageManager,
-                yay::YayPackageManager,
-            ));
-        } else {
-            return Some(PackageManager::Pacman(pacman::PacmanPackageManager));
-        }
+                aur_helper::AurHelperPackageManager { helper },
+            ),
+            None => PackageManager::Pacman(pacman::PacmanPackageManager),
+        });
     }
 
     None
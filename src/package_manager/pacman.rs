@@ -1,23 +1,60 @@
+use super::alpm_db::{self, DbPackage};
+use super::changelog::ChangelogEntry;
+use super::details::{self, PackageDetails};
+use super::pacman_conf::{self, PACMAN_CONF};
+use super::repo_health::{self, RepositoryIssue};
+use super::strategy::UpgradeStrategy;
+use super::version;
 use super::Package;
+use crate::utils;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fs;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
 use std::process::Command as StdCommand;
 use tokio::task;
 
+/// pacman's system database directory
+const PACMAN_DB: &str = "/var/lib/pacman";
+
 #[derive(Clone)]
 pub struct PacmanPackageManager;
 
 impl PacmanPackageManager {
+    /// Compute pending updates from the pacman databases
+    ///
+    /// Reads the temporary database synced by [`Self::sync_databases`] and
+    /// compares every installed package against the first repository providing
+    /// it, in pacman.conf order. Packages no repository provides are foreign
+    /// (AUR) and left to the AUR helper.
     pub async fn check_updates(&self) -> Result<Vec<Package>, String> {
         task::spawn_blocking(|| {
-            let output = StdCommand::new("checkupdates")
-                .output()
-                .map_err(|e| format!("Failed to run checkupdates: {}", e))?;
+            let db_path = checkupdates_sync_path()?;
 
-            if !output.status.success() {
-                return Ok(Vec::new());
-            }
+            let installed = alpm_db::read_local(Path::new(PACMAN_DB))?;
+            let available = read_sync_dbs(&db_path)?;
 
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            let packages = parse_pacman_output(&stdout);
+            let mut packages: Vec<Package> = installed
+                .values()
+                .filter_map(|local| {
+                    let new = available.get(&local.name)?;
+                    if version::compare_pacman(&new.version, &local.version) != Ordering::Greater {
+                        return None;
+                    }
+                    Some(Package {
+                        name: local.name.clone(),
+                        current_version: local.version.clone(),
+                        new_version: new.version.clone(),
+                        is_aur: false,
+                        repository: new.repository.clone(),
+                        download_size: new.download_size,
+                        installed_size: new.installed_size,
+                        ..Default::default()
+                    })
+                })
+                .collect();
+            packages.sort_by(|a, b| a.name.cmp(&b.name));
 
             Ok(packages)
         })
@@ -28,21 +65,23 @@ impl PacmanPackageManager {
     /// Launch Pacman upgrade in a terminal emulator
     ///
     /// Spawns the specified terminal with a Pacman system upgrade command.
-    /// Uses pkexec for privilege escalation and --noconfirm for non-interactive upgrades.
+    /// Uses pkexec for privilege escalation.
     ///
     /// # Arguments
     /// * `terminal` - Terminal emulator to use (e.g., "cosmic-term", "konsole")
+    /// * `strategy` - Configured upgrade options (subcommand, prompts, extra flags)
     ///
     /// # Returns
     /// * `Ok(())` - Terminal process spawned successfully
-    /// * `Err(String)` - Failed to spawn terminal
-    pub async fn run_upgrade(&self, terminal: &str) -> Result<(), String> {
+    /// * `Err(String)` - Failed to spawn terminal, or an extra flag is not allowed
+    pub async fn run_upgrade(&self, terminal: &str, strategy: &UpgradeStrategy) -> Result<(), String> {
         let terminal = terminal.to_string();
+        let upgrade = strategy.pacman_command()?.join(" ");
         task::spawn_blocking(move || {
             // Use bash -c to chain the upgrade command with a prompt to keep terminal open
-            let command = "pkexec pacman -Syu --noconfirm; echo ''; echo 'Press Enter to close...'; read";
+            let command = format!("{}; echo ''; echo 'Press Enter to close...'; read", upgrade);
             StdCommand::new(&terminal)
-                .args(["-e", "bash", "-c", command])
+                .args(["-e", "bash", "-c", &command])
                 .spawn()
                 .map_err(|e| format!("Failed to launch terminal '{}': {}", terminal, e))?;
 
@@ -52,6 +91,165 @@ impl PacmanPackageManager {
         .map_err(|e| format!("Task join error: {}", e))?
     }
 
+    /// Read the changelog shipped with the installed package (`pacman -Qc`)
+    ///
+    /// Few Arch packages ship one and it only covers the installed version, so the
+    /// whole text is returned as a single entry.
+    pub async fn changelog(&self, package: &Package) -> Result<Vec<ChangelogEntry>, String> {
+        let package = package.clone();
+        task::spawn_blocking(move || {
+            let output = StdCommand::new("pacman")
+                .args(["-Qc", &package.name])
+                .output()
+                .map_err(|e| format!("Failed to run pacman: {}", e))?;
+
+            if !output.status.success() {
+                return Err(format!("{} does not ship a changelog", package.name));
+            }
+
+            let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            Ok(vec![ChangelogEntry {
+                version: package.current_version.clone(),
+                date: String::new(),
+                text,
+            }])
+        })
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+    }
+
+    /// Collect metadata for the package detail page from the pacman databases
+    ///
+    /// The new version is read from the temporary sync database, since the
+    /// system database may not know it yet.
+    pub async fn details(&self, package: &Package) -> Result<PackageDetails, String> {
+        let package = package.clone();
+        task::spawn_blocking(move || {
+            let db_path = checkupdates_db_path().ok_or("Sync database not found, run a check first")?;
+            let available = read_sync_dbs(&db_path)?;
+            let new = available
+                .get(&package.name)
+                .ok_or_else(|| format!("No sync information for {}", package.name))?;
+
+            let installed = alpm_db::read_local_entry(
+                &Path::new(PACMAN_DB)
+                    .join("local")
+                    .join(format!("{}-{}", package.name, package.current_version)),
+            );
+
+            Ok(details::from_pacman_db(new, installed.as_ref()))
+        })
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+    }
+
+    /// Read the packages pacman refuses to upgrade
+    ///
+    /// Collects `IgnorePkg` entries (which may be globs) and the members of every
+    /// `IgnoreGroup` from the `[options]` section of pacman.conf and its includes.
+    ///
+    /// # Returns
+    /// (pattern, source) pairs, e.g. ("linux", "IgnorePkg in /etc/pacman.conf")
+    pub async fn holds(&self) -> Vec<(String, String)> {
+        task::spawn_blocking(|| {
+            let mut holds = Vec::new();
+
+            for directive in pacman_conf::read(Path::new(PACMAN_CONF)) {
+                if directive.section != "options" {
+                    continue;
+                }
+                match directive.key.as_str() {
+                    "IgnorePkg" => {
+                        for pattern in directive.value.split_whitespace() {
+                            holds.push((
+                                pattern.to_string(),
+                                format!("IgnorePkg in {}", directive.file.display()),
+                            ));
+                        }
+                    }
+                    "IgnoreGroup" => {
+                        for group in directive.value.split_whitespace() {
+                            let source = format!("IgnoreGroup {} in {}", group, directive.file.display());
+                            for member in group_members(group) {
+                                holds.push((member, source.clone()));
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+
+            holds
+        })
+        .await
+        .unwrap_or_default()
+    }
+
+    /// Add or remove a package in pacman.conf's `IgnorePkg` list
+    ///
+    /// The edited file is prepared unprivileged and installed over
+    /// /etc/pacman.conf through pkexec, keeping a `pacman.conf~` backup.
+    /// Packages held through an included file or `IgnoreGroup` have to be
+    /// released there.
+    pub async fn set_hold(&self, name: String, hold: bool) -> Result<(), String> {
+        task::spawn_blocking(move || {
+            let contents = fs::read_to_string(PACMAN_CONF)
+                .map_err(|e| format!("Failed to read {}: {}", PACMAN_CONF, e))?;
+
+            let Some(updated) = pacman_conf::set_ignore_pkg(&contents, &name, hold) else {
+                return if hold {
+                    Ok(()) // Already held
+                } else {
+                    Err(format!(
+                        "{} is not listed in IgnorePkg of {}, release it where it is held",
+                        name, PACMAN_CONF
+                    ))
+                };
+            };
+
+            utils::install_as_root(&updated, Path::new(PACMAN_CONF), true)
+        })
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+    }
+
+    /// Upgrade only the given packages without any interaction
+    ///
+    /// Arch Linux does not support partial upgrades, so selective upgrades are
+    /// always refused. Use a full `pacman -Syu` instead.
+    pub async fn upgrade_packages(&self, _names: Vec<String>) -> Result<(), String> {
+        Err("Partial upgrades are not supported on Arch Linux, run a full system upgrade instead".to_string())
+    }
+
+    /// Download pending upgrades into the pacman package cache without installing them
+    ///
+    /// Uses the temporary database the update check keeps in sync, so the real
+    /// sync database is never touched and no partial-upgrade state is created.
+    /// The later `pacman -Syu` finds the files in `/var/cache/pacman/pkg` and only
+    /// has to install them.
+    pub async fn download_updates(&self) -> Result<(), String> {
+        task::spawn_blocking(|| {
+            let db_path = checkupdates_db_path()
+                .ok_or("Sync database not found, run a check first")?;
+
+            let output = StdCommand::new("pkexec")
+                .arg("pacman")
+                .args(["-Swu", "--noconfirm", "--dbpath"])
+                .arg(&db_path)
+                .output()
+                .map_err(|e| format!("Failed to download updates: {}", e))?;
+
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                return Err(format!("Download failed: {}", stderr));
+            }
+
+            Ok(())
+        })
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+    }
+
     pub async fn is_running(&self) -> bool {
         task::spawn_blocking(|| {
             // Check both lock file and running processes
@@ -75,56 +273,154 @@ impl PacmanPackageManager {
         "Pacman"
     }
 
-    pub async fn refresh_cache(&self) -> Result<(), String> {
-        // Pacman's database is automatically updated by checkupdates
-        // and pacman -Syu, so we don't need a separate refresh
-        // Use spawn_blocking for consistency even though this is a no-op
-        task::spawn_blocking(|| Ok(()))
+    /// Sync the temporary database the update check reads
+    ///
+    /// `/var/lib/pacman/sync` is never touched, so syncing can not leave the
+    /// system in a partial-upgrade state. Runs before every check, like
+    /// `checkupdates` does.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<RepositoryIssue>)` - Problems pacman reported; repositories that
+    ///   failed keep their last synced copy
+    /// * `Err(String)` - Syncing failed without an earlier copy to fall back to
+    pub async fn sync_databases(&self) -> Result<Vec<RepositoryIssue>, String> {
+        task::spawn_blocking(|| sync_temp_db(&checkupdates_sync_path()?))
             .await
             .map_err(|e| format!("Task join error: {}", e))?
     }
+
+    /// Refresh after an upgrade, which on Arch is the same unprivileged sync
+    pub async fn refresh_cache(&self) -> Result<Vec<RepositoryIssue>, String> {
+        self.sync_databases().await
+    }
+
+    /// Count installed packages that are older than the system sync database
+    ///
+    /// `pacman -Qu` compares against `/var/lib/pacman/sync`, not the temporary
+    /// copy. Anything listed means the real database was synced (`pacman -Sy`,
+    /// `paru -Sy`, ...) without upgrading, and installing a single package now
+    /// would be a partial upgrade.
+    pub async fn out_of_sync_count(&self) -> usize {
+        task::spawn_blocking(|| {
+            StdCommand::new("pacman")
+                .arg("-Qu")
+                .output()
+                .map(|output| {
+                    String::from_utf8_lossy(&output.stdout)
+                        .lines()
+                        .filter(|line| !line.trim().is_empty() && !line.contains("[ignored]"))
+                        .count()
+                })
+                .unwrap_or(0)
+        })
+        .await
+        .unwrap_or(0)
+    }
+}
+
+/// List the installed members of a package group
+fn group_members(group: &str) -> Vec<String> {
+    StdCommand::new("pacman")
+        .args(["-Qgq", group])
+        .output()
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .map(|line| line.trim().to_string())
+                .filter(|line| !line.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
 }
 
-/// Parse Pacman checkupdates output into a list of packages
+/// Locate the temporary sync database (shared with `checkupdates`)
 ///
-/// Expected format: "package current_version -> new_version"
-/// Example: "firefox 121.0-1 -> 122.0-1"
-fn parse_pacman_output(output: &str) -> Vec<Package> {
-    output
-        .lines()
-        .filter_map(|line| {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-
-            // Validate we have all required fields
-            if parts.len() < 4 {
-                if !line.trim().is_empty() {
-                    eprintln!("Warning: Skipping malformed Pacman line (insufficient fields): {}", line);
-                }
-                return None;
-            }
+/// Honours `CHECKUPDATES_DB`, then tries the UID-based directory used by current
+/// pacman-contrib and the username-based one used by older releases.
+fn checkupdates_db_path() -> Option<PathBuf> {
+    if let Ok(path) = std::env::var("CHECKUPDATES_DB") {
+        return Some(PathBuf::from(path));
+    }
 
-            // Validate the arrow separator is in the expected position
-            if parts.get(2)? != &"->" {
-                eprintln!("Warning: Skipping Pacman line with unexpected format (missing '->'): {}", line);
-                return None;
-            }
+    let tmp_dir = std::env::temp_dir();
+    let uid = std::fs::metadata("/proc/self").ok()?.uid();
 
-            let name = parts.first()?.to_string();
-            let current_version = parts.get(1)?.to_string();
-            let new_version = parts.get(3)?.to_string();
+    let mut candidates = vec![tmp_dir.join(format!("checkup-db-{}", uid))];
+    if let Ok(user) = std::env::var("USER") {
+        candidates.push(tmp_dir.join(format!("checkup-db-{}", user)));
+    }
 
-            // Validate non-empty fields
-            if name.is_empty() || current_version.is_empty() || new_version.is_empty() {
-                eprintln!("Warning: Skipping Pacman line with empty fields: {}", line);
-                return None;
-            }
+    candidates.into_iter().find(|path| path.join("sync").exists())
+}
 
-            Some(Package {
-                name,
-                current_version,
-                new_version,
-                is_aur: false,
-            })
-        })
-        .collect()
+/// Directory the temporary database is synced into, whether or not it exists yet
+fn checkupdates_sync_path() -> Result<PathBuf, String> {
+    if let Ok(path) = std::env::var("CHECKUPDATES_DB") {
+        return Ok(PathBuf::from(path));
+    }
+
+    let uid = fs::metadata("/proc/self")
+        .map_err(|e| format!("Failed to read user id: {}", e))?
+        .uid();
+    Ok(std::env::temp_dir().join(format!("checkup-db-{}", uid)))
+}
+
+/// Download the sync databases into a private database directory
+///
+/// Mirrors what `checkupdates` does, and uses the same directory so both share
+/// one copy: the databases are synced into a private `--dbpath` under fakeroot,
+/// with `local` linked to the real installed database.
+fn sync_temp_db(db_path: &Path) -> Result<Vec<RepositoryIssue>, String> {
+    fs::create_dir_all(db_path).map_err(|e| format!("Failed to create {}: {}", db_path.display(), e))?;
+
+    let local = db_path.join("local");
+    if fs::symlink_metadata(&local).is_err() {
+        std::os::unix::fs::symlink(Path::new(PACMAN_DB).join("local"), &local)
+            .map_err(|e| format!("Failed to link local database: {}", e))?;
+    }
+
+    let output = StdCommand::new("fakeroot")
+        .args(["--", "pacman", "-Sy", "--dbpath"])
+        .arg(db_path)
+        .args(["--logfile", "/dev/null"])
+        .env("LC_ALL", "C") // Messages are parsed below
+        .output()
+        .map_err(|e| format!("Failed to run fakeroot (required to check for updates): {}", e))?;
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let mut issues = repo_health::parse_pacman_sync(&stderr);
+
+    if output.status.success() {
+        // Every database was synced in the end, failing mirrors were skipped
+        for issue in &mut issues {
+            issue.is_error = false;
+        }
+        return Ok(issues);
+    }
+    if !issues.is_empty() && db_path.join("sync").exists() {
+        return Ok(issues);
+    }
+    Err(format!("Cache refresh failed: {}", stderr))
+}
+
+/// Read the sync databases of every configured repository
+///
+/// # Returns
+/// Packages by name, taken from the first repository (in pacman.conf order)
+/// that provides them
+fn read_sync_dbs(db_path: &Path) -> Result<HashMap<String, DbPackage>, String> {
+    let mut packages = HashMap::new();
+
+    for repository in pacman_conf::repositories(&pacman_conf::read(Path::new(PACMAN_CONF))) {
+        let db_file = db_path.join("sync").join(format!("{}.db", repository));
+        if !db_file.exists() {
+            eprintln!("Warning: No sync database for repository {}", repository);
+            continue;
+        }
+        for package in alpm_db::read_sync(&db_file, &repository)? {
+            packages.entry(package.name.clone()).or_insert(package);
+        }
+    }
+
+    Ok(packages)
 }
@@ -0,0 +1,251 @@
+use crate::utils;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Main pacman configuration file
+pub const PACMAN_CONF: &str = "/etc/pacman.conf";
+
+/// Maximum nesting of `Include` directives (pacman itself stops at 10)
+const MAX_INCLUDE_DEPTH: usize = 10;
+
+/// A single `Key = value` (or bare `Key`) line from pacman.conf or an included file
+#[derive(Debug, Clone)]
+pub struct Directive {
+    /// Section the line belongs to ("options" or a repository name)
+    pub section: String,
+    pub key: String,
+    pub value: String,
+    /// File the line was read from
+    pub file: PathBuf,
+}
+
+/// Read pacman.conf and every file it includes, in order
+///
+/// `Include` lines are followed (with `*` globs in the file name) and the
+/// section carries over into and out of included files, as pacman does.
+pub fn read(path: &Path) -> Vec<Directive> {
+    let mut directives = Vec::new();
+    let mut section = String::new();
+    read_into(path, &mut section, &mut directives, 0);
+    directives
+}
+
+/// List the sync repositories in the order pacman searches them
+///
+/// Every section except `[options]` is a repository, and the first one
+/// providing a package wins.
+pub fn repositories(directives: &[Directive]) -> Vec<String> {
+    let mut repositories: Vec<String> = Vec::new();
+    for directive in directives {
+        if directive.section != "options"
+            && !directive.section.is_empty()
+            && !repositories.contains(&directive.section)
+        {
+            repositories.push(directive.section.clone());
+        }
+    }
+    repositories
+}
+
+fn read_into(path: &Path, section: &mut String, directives: &mut Vec<Directive>, depth: usize) {
+    if depth > MAX_INCLUDE_DEPTH {
+        eprintln!("Warning: Too many nested includes at {}", path.display());
+        return;
+    }
+    let Ok(contents) = fs::read_to_string(path) else {
+        return;
+    };
+
+    for line in contents.lines() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+            *section = name.trim().to_string();
+            continue;
+        }
+
+        let (key, value) = match line.split_once('=') {
+            Some((key, value)) => (key.trim(), value.trim()),
+            None => (line, ""),
+        };
+
+        if key == "Include" {
+            for include in expand_include(value) {
+                read_into(&include, section, directives, depth + 1);
+            }
+            continue;
+        }
+
+        directives.push(Directive {
+            section: section.clone(),
+            key: key.to_string(),
+            value: value.to_string(),
+            file: path.to_path_buf(),
+        });
+    }
+}
+
+/// Resolve an `Include` value, expanding a glob in the file name
+fn expand_include(value: &str) -> Vec<PathBuf> {
+    let path = Path::new(value);
+    let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+        return Vec::new();
+    };
+    if !file_name.contains(['*', '?']) {
+        return vec![path.to_path_buf()];
+    }
+
+    let dir = path.parent().unwrap_or(Path::new("/"));
+    let mut matches: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| {
+                    entry
+                        .file_name()
+                        .to_str()
+                        .is_some_and(|name| utils::glob_match(file_name, name))
+                })
+                .map(|entry| entry.path())
+                .collect()
+        })
+        .unwrap_or_default();
+    matches.sort();
+    matches
+}
+
+/// Add or remove a package in the `IgnorePkg` list of the `[options]` section
+///
+/// Holding appends to the first `IgnorePkg` line, or adds one right after the
+/// `[options]` header. Releasing removes the name from every `IgnorePkg` line
+/// of `[options]` in this file. Comments and layout are otherwise kept.
+///
+/// # Returns
+/// * `Some(String)` - The updated file contents
+/// * `None` - Nothing to change (already held, not held here, or no `[options]`)
+pub fn set_ignore_pkg(contents: &str, name: &str, hold: bool) -> Option<String> {
+    let mut lines: Vec<String> = contents.lines().map(|line| line.to_string()).collect();
+    let mut in_options = false;
+    let mut options_header = None;
+    let mut ignore_lines = Vec::new();
+
+    for (index, line) in lines.iter().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            in_options = trimmed == "[options]";
+            if in_options && options_header.is_none() {
+                options_header = Some(index);
+            }
+        } else if in_options {
+            let key = trimmed.split('=').next().unwrap_or("").trim();
+            if key == "IgnorePkg" {
+                ignore_lines.push(index);
+            }
+        }
+    }
+
+    let ignored_names = |line: &str| -> Vec<String> {
+        let value = line.split('#').next().unwrap_or("");
+        value
+            .split_once('=')
+            .map(|(_, names)| names.split_whitespace().map(|n| n.to_string()).collect())
+            .unwrap_or_default()
+    };
+
+    if hold {
+        if ignore_lines.iter().any(|&i| ignored_names(&lines[i]).iter().any(|n| n == name)) {
+            return None;
+        }
+        match ignore_lines.first() {
+            Some(&index) => {
+                let names = ignored_names(&lines[index]);
+                lines[index] = format!("IgnorePkg   = {}", [names, vec![name.to_string()]].concat().join(" "));
+            }
+            None => lines.insert(options_header? + 1, format!("IgnorePkg   = {}", name)),
+        }
+    } else {
+        let mut changed = false;
+        for &index in &ignore_lines {
+            let names = ignored_names(&lines[index]);
+            if names.iter().any(|n| n == name) {
+                let kept: Vec<String> = names.into_iter().filter(|n| n != name).collect();
+                // An emptied line goes back to pacman.conf's commented-out default
+                lines[index] = if kept.is_empty() {
+                    "#IgnorePkg   =".to_string()
+                } else {
+                    format!("IgnorePkg   = {}", kept.join(" "))
+                };
+                changed = true;
+            }
+        }
+        if !changed {
+            return None;
+        }
+    }
+
+    let mut updated = lines.join("\n");
+    updated.push('\n');
+    Some(updated)
+}
+
+/// Recognise a repository section header, also when commented out
+///
+/// # Returns
+/// * `Some((enabled, name))` - For "[name]" (enabled) or "#[name]" (disabled)
+/// * `None` - The line is not a section header
+pub fn repository_header(line: &str) -> Option<(bool, &str)> {
+    let trimmed = line.trim();
+    let (enabled, header) = match trimmed.strip_prefix('#') {
+        Some(rest) => (false, rest.trim_start()),
+        None => (true, trimmed),
+    };
+    let name = header.strip_prefix('[')?.strip_suffix(']')?.trim();
+    (!name.is_empty() && !name.contains(' ')).then_some((enabled, name))
+}
+
+/// Comment out or restore a repository section
+///
+/// The section is the header plus the lines after it up to a blank line or
+/// the next header. Disabling puts a `#` in front of each of them; enabling
+/// removes it from the header and from `Key = value` lines, leaving prose
+/// comments alone.
+///
+/// # Returns
+/// * `Some(String)` - The updated file contents
+/// * `None` - No such repository in this file, or already in the requested state
+pub fn set_repository_enabled(contents: &str, name: &str, enabled: bool) -> Option<String> {
+    let mut lines: Vec<String> = contents.lines().map(|line| line.to_string()).collect();
+    let header = lines
+        .iter()
+        .position(|line| repository_header(line).is_some_and(|(_, section)| section == name))?;
+    if repository_header(&lines[header]).is_some_and(|(is_enabled, _)| is_enabled == enabled) {
+        return None;
+    }
+
+    let end = (header + 1..lines.len())
+        .find(|&i| lines[i].trim().is_empty() || repository_header(&lines[i]).is_some())
+        .unwrap_or(lines.len());
+
+    for (offset, line) in lines[header..end].iter_mut().enumerate() {
+        let trimmed = line.trim_start();
+        if enabled {
+            let uncommented = trimmed.trim_start_matches('#').trim_start();
+            let is_directive = uncommented
+                .split('=')
+                .next()
+                .is_some_and(|key| !key.trim().is_empty() && !key.trim().contains(' '));
+            if offset == 0 || (trimmed.starts_with('#') && is_directive) {
+                *line = uncommented.to_string();
+            }
+        } else if !trimmed.starts_with('#') {
+            *line = format!("#{}", trimmed);
+        }
+    }
+
+    let mut updated = lines.join("\n");
+    updated.push('\n');
+    Some(updated)
+}
@@ -0,0 +1,166 @@
+use super::alpm_db;
+use super::aur_rpc::DEFAULT_AUR_URL;
+use super::{is_vcs_package, Package};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command as StdCommand;
+
+/// Number of `.SRCINFO` revisions searched for the installed version
+const MAX_SRCINFO_REVISIONS: usize = 100;
+
+/// Maximum number of diff lines returned, longer diffs are cut with a note
+const MAX_DIFF_LINES: usize = 1000;
+
+/// Files shown in the review: the PKGBUILD and install scripts
+const REVIEWED_FILES: &[&str] = &["PKGBUILD", "*.install"];
+
+/// Diff the PKGBUILD and install scripts between the installed and the new version
+///
+/// The helper's clone is used when it exists (fetching only updates remote refs,
+/// so the helper's own review state is untouched). Otherwise the package is
+/// cloned from the AUR into the applet's cache. The revisions of the installed
+/// and the new version are found by walking the `.SRCINFO` history.
+///
+/// VCS packages are built with a pkgver that never appears in `.SRCINFO`, so
+/// for them the diff covers what changed since the helper's checkout, which is
+/// the last revision it reviewed. Without a helper clone there is no such
+/// revision and no diff.
+///
+/// # Arguments
+/// * `helper_clone_dir` - Directory holding the helper's clones (e.g. `~/.cache/paru/clone`),
+///   None for helpers that keep no clones
+/// * `package` - AUR package with a pending update
+///
+/// # Returns
+/// * `Ok(String)` - Unified diff (empty when neither file changed)
+/// * `Err(String)` - No clone could be found or created, a version is not in the history,
+///   or the package is a VCS package without a helper clone
+pub fn aur_diff(helper_clone_dir: Option<&Path>, package: &Package) -> Result<String, String> {
+    let base = pkgbase(&package.name, &package.current_version);
+    let clone_dir = clone_for(helper_clone_dir, &base)?;
+    let is_helper_clone = helper_clone_dir.is_some_and(|dir| clone_dir == dir.join(&base));
+
+    // Make sure the clone knows about the new version
+    let _ = git(&clone_dir, &["fetch", "--quiet", "origin"]);
+
+    let (from, to) = if is_vcs_package(package) {
+        if !is_helper_clone {
+            return Err(format!(
+                "PKGBUILD changes are not available for VCS packages like {} without the AUR helper's clone",
+                base
+            ));
+        }
+        ("HEAD".to_string(), "origin/HEAD".to_string())
+    } else {
+        let revision = |version: &str| {
+            version_revision(&clone_dir, version)
+                .ok_or_else(|| format!("Version {} of {} was not found in the AUR history", version, base))
+        };
+        (revision(&package.current_version)?, revision(&package.new_version)?)
+    };
+
+    let mut args = vec!["diff", "--no-color", from.as_str(), to.as_str(), "--"];
+    args.extend(REVIEWED_FILES);
+    let diff = git(&clone_dir, &args)?;
+
+    let lines: Vec<&str> = diff.lines().collect();
+    if lines.len() > MAX_DIFF_LINES {
+        Ok(format!(
+            "{}\n... {} more lines, see {}",
+            lines[..MAX_DIFF_LINES].join("\n"),
+            lines.len() - MAX_DIFF_LINES,
+            clone_dir.display()
+        ))
+    } else {
+        Ok(diff)
+    }
+}
+
+/// Look up the package base of an installed package in the local pacman database
+///
+/// Split packages share one AUR repository named after the base. Falls back to
+/// the package name when the database entry cannot be read.
+pub fn pkgbase(name: &str, version: &str) -> String {
+    alpm_db::read_local_entry(&Path::new("/var/lib/pacman/local").join(format!("{}-{}", name, version)))
+        .map(|package| package.base)
+        .filter(|base| !base.is_empty())
+        .unwrap_or_else(|| name.to_string())
+}
+
+/// Find the helper's clone of a package, or clone it into the applet's cache
+pub fn clone_for(helper_clone_dir: Option<&Path>, base: &str) -> Result<PathBuf, String> {
+    if let Some(helper_clone) = helper_clone_dir.map(|dir| dir.join(base)) {
+        if helper_clone.join(".git").exists() {
+            return Ok(helper_clone);
+        }
+    }
+
+    let own_clone = dirs::cache_dir()
+        .ok_or("Could not determine cache directory")?
+        .join("cosmic-ext-applet-updates/aur")
+        .join(base);
+    if own_clone.join(".git").exists() {
+        return Ok(own_clone);
+    }
+
+    if let Some(parent) = own_clone.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let output = StdCommand::new("git")
+        .args(["clone", "--quiet", &format!("{}/{}.git", DEFAULT_AUR_URL, base)])
+        .arg(&own_clone)
+        .output()
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Failed to clone {} from the AUR: {}", base, stderr));
+    }
+
+    Ok(own_clone)
+}
+
+/// Find the newest commit whose `.SRCINFO` describes the given version
+fn version_revision(clone_dir: &Path, version: &str) -> Option<String> {
+    let log = git(clone_dir, &["log", "--format=%H", "origin/HEAD", "--", ".SRCINFO"]).ok()?;
+
+    log.lines().take(MAX_SRCINFO_REVISIONS).find_map(|commit| {
+        let srcinfo = git(clone_dir, &["show", &format!("{}:.SRCINFO", commit)]).ok()?;
+        (srcinfo_version(&srcinfo)? == version).then(|| commit.to_string())
+    })
+}
+
+/// Build `[epoch:]pkgver-pkgrel` from the pkgbase section of a `.SRCINFO`
+fn srcinfo_version(srcinfo: &str) -> Option<String> {
+    let value = |key: &str| {
+        srcinfo
+            .lines()
+            .take_while(|line| !line.starts_with("pkgname"))
+            .filter_map(|line| line.trim().split_once('='))
+            .find(|(k, _)| k.trim() == key)
+            .map(|(_, v)| v.trim().to_string())
+    };
+
+    let pkgver = value("pkgver")?;
+    let pkgrel = value("pkgrel")?;
+    Some(match value("epoch") {
+        Some(epoch) if epoch != "0" => format!("{}:{}-{}", epoch, pkgver, pkgrel),
+        _ => format!("{}-{}", pkgver, pkgrel),
+    })
+}
+
+/// Run a git command in a clone and return its output
+fn git(clone_dir: &Path, args: &[&str]) -> Result<String, String> {
+    let output = StdCommand::new("git")
+        .arg("-C")
+        .arg(clone_dir)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("git {} failed: {}", args.first().unwrap_or(&""), stderr));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
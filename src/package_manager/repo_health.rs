@@ -0,0 +1,243 @@
+/// Repository name used for problems reaching the AUR
+pub const AUR_REPOSITORY: &str = "AUR";
+
+/// What is wrong with a repository
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IssueKind {
+    /// The signing key is not in the keyring (NO_PUBKEY, unknown key)
+    MissingKey,
+    /// The signing key has expired (EXPKEYSIG)
+    ExpiredKey,
+    /// The Release file or database is gone (404, no Release file)
+    NotFound,
+    /// The server could not be resolved or reached
+    Unreachable,
+    /// The same source is configured more than once
+    DuplicateSource,
+    /// The repository has no packages for the system architecture
+    UnsupportedArchitecture,
+    /// Any other warning or error reported for the repository
+    Other,
+}
+
+impl IssueKind {
+    /// Short label shown in the Repositories panel
+    pub fn label(&self) -> &'static str {
+        match self {
+            IssueKind::MissingKey => "missing signing key",
+            IssueKind::ExpiredKey => "expired signing key",
+            IssueKind::NotFound => "not found",
+            IssueKind::Unreachable => "unreachable",
+            IssueKind::DuplicateSource => "configured twice",
+            IssueKind::UnsupportedArchitecture => "architecture not supported",
+            IssueKind::Other => "problem",
+        }
+    }
+}
+
+/// A problem with one repository, reported while refreshing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepositoryIssue {
+    pub kind: IssueKind,
+    /// Repository as the tool names it, e.g. "https://ppa.launchpadcontent.net/x/y/ubuntu noble" or "core"
+    pub repository: String,
+    /// The tool's own message
+    pub message: String,
+    /// The repository was not updated (error), rather than updated with a warning
+    pub is_error: bool,
+}
+
+/// Summary lines apt prints after the per-repository messages
+const APT_SUMMARIES: &[&str] = &[
+    "Some index files failed to download",
+    "Failed to fetch",
+    "See apt-secure(8) manpage",
+    "Updating from such a repository can't be done securely",
+];
+
+/// apt messages meaning the server could not be reached
+const APT_UNREACHABLE: &[&str] = &[
+    "Could not resolve",
+    "Temporary failure resolving",
+    "Could not connect",
+    "Connection timed out",
+    "Unable to connect",
+];
+
+/// pacman (curl) messages meaning the server could not be reached
+const PACMAN_UNREACHABLE: &[&str] = &[
+    "Could not resolve host",
+    "Failed to connect",
+    "Connection timed out",
+    "Operation timed out",
+    "Operation too slow",
+];
+
+/// Check whether an error from curl (or pacman, which uses libcurl) means the server could not be reached
+pub fn is_network_error(message: &str) -> bool {
+    PACMAN_UNREACHABLE.iter().any(|pattern| message.contains(pattern))
+}
+
+/// Extract repository issues from `apt update` output (stdout and stderr)
+///
+/// Reads `W:` and `E:` lines, `Err:` lines with their indented reason, and the
+/// `N:` notes about unsupported architectures. Several messages about the same
+/// problem (NO_PUBKEY warning plus "is not signed" error) become one issue.
+pub fn parse_apt_update(output: &str) -> Vec<RepositoryIssue> {
+    // Join indented continuation lines onto the message they belong to
+    let mut messages: Vec<(String, String)> = Vec::new();
+    for line in output.lines() {
+        if line.starts_with("  ") {
+            if let Some((_, message)) = messages.last_mut() {
+                message.push(' ');
+                message.push_str(line.trim());
+            }
+            continue;
+        }
+        let Some((tag, message)) = line.split_once(':') else {
+            continue;
+        };
+        if matches!(tag, "W" | "E" | "Err" | "N") {
+            // "Err:5 http://..." carries a fetch counter
+            let message = message.trim_start_matches(|c: char| c.is_ascii_digit()).trim();
+            messages.push((tag.to_string(), message.to_string()));
+        }
+    }
+
+    let mut issues = Vec::new();
+    for (tag, message) in messages {
+        if tag == "N" && !message.contains("doesn't support architecture") {
+            continue;
+        }
+        if APT_SUMMARIES.iter().any(|summary| message.starts_with(summary)) {
+            continue;
+        }
+
+        let kind = if message.contains("NO_PUBKEY") || message.contains("is not signed") {
+            IssueKind::MissingKey
+        } else if message.contains("EXPKEYSIG") || message.contains("KEYEXPIRED") {
+            IssueKind::ExpiredKey
+        } else if message.contains("404") || message.contains("does not have a Release file") {
+            IssueKind::NotFound
+        } else if APT_UNREACHABLE.iter().any(|pattern| message.contains(pattern)) {
+            IssueKind::Unreachable
+        } else if message.contains("is configured multiple times") {
+            IssueKind::DuplicateSource
+        } else if message.contains("doesn't support architecture") {
+            IssueKind::UnsupportedArchitecture
+        } else {
+            IssueKind::Other
+        };
+
+        // Duplicates name the source files rather than a URI
+        let repository = match message.split_once("configured multiple times in ") {
+            Some((_, files)) => files.trim_end_matches('.').to_string(),
+            None => apt_repository(&message),
+        };
+        let issue = RepositoryIssue {
+            kind,
+            repository,
+            message,
+            is_error: tag == "E" || tag == "Err",
+        };
+        push_issue(&mut issues, issue);
+    }
+
+    issues
+}
+
+/// Extract repository issues from `pacman -Sy` output
+///
+/// pacman names the database file ("core.db") or the repository at the start
+/// of its error lines. Warnings about missing local databases are left out.
+pub fn parse_pacman_sync(output: &str) -> Vec<RepositoryIssue> {
+    let mut issues = Vec::new();
+
+    for line in output.lines() {
+        let (is_error, message) = match line.split_once(": ") {
+            Some(("error", message)) => (true, message.trim()),
+            Some(("warning", message)) => (false, message.trim()),
+            _ => continue,
+        };
+        // Summary of the errors above
+        if message.starts_with("failed to synchronize all databases") || message.contains("use '-Sy'") {
+            continue;
+        }
+
+        let kind = if message.contains("is unknown trust") || message.contains("unknown public key") {
+            IssueKind::MissingKey
+        } else if message.contains("expired") {
+            IssueKind::ExpiredKey
+        } else if message.contains("error: 404") || message.contains("404 Not Found") {
+            IssueKind::NotFound
+        } else if is_network_error(message) {
+            IssueKind::Unreachable
+        } else if message.contains("database already registered") {
+            IssueKind::DuplicateSource
+        } else {
+            IssueKind::Other
+        };
+
+        let issue = RepositoryIssue {
+            kind,
+            repository: pacman_repository(message),
+            message: message.to_string(),
+            is_error,
+        };
+        push_issue(&mut issues, issue);
+    }
+
+    issues
+}
+
+/// Add an issue, merging it into an earlier one about the same problem
+fn push_issue(issues: &mut Vec<RepositoryIssue>, issue: RepositoryIssue) {
+    match issues
+        .iter_mut()
+        .find(|known| known.kind == issue.kind && known.repository == issue.repository)
+    {
+        Some(known) => known.is_error |= issue.is_error,
+        None => issues.push(issue),
+    }
+}
+
+/// Find the repository an apt message is about
+///
+/// Uses the first quoted 'URI suite file' or, failing that, the first URI and
+/// the suite after it. Trailing index names ("InRelease", "Release") are dropped.
+fn apt_repository(message: &str) -> String {
+    let candidate = message
+        .split('\'')
+        .skip(1)
+        .step_by(2)
+        .find(|quoted| quoted.contains("://") || quoted.starts_with("cdrom:"))
+        .map(|quoted| quoted.to_string())
+        .or_else(|| {
+            let words: Vec<&str> = message.split_whitespace().collect();
+            let start = words.iter().position(|word| word.contains("://"))?;
+            Some(words[start..(start + 2).min(words.len())].join(" "))
+        });
+
+    match candidate {
+        Some(repository) => repository
+            .split_whitespace()
+            .filter(|word| !matches!(word.trim_end_matches(':'), "InRelease" | "Release" | "Release.gpg"))
+            .map(|word| word.trim_end_matches(':'))
+            .collect::<Vec<_>>()
+            .join(" "),
+        None => String::new(),
+    }
+}
+
+/// Find the repository a pacman message is about
+///
+/// Handles "failed retrieving file 'core.db' from ..." and "core: signature ...".
+fn pacman_repository(message: &str) -> String {
+    if let Some(file) = message.split('\'').nth(1) {
+        return file.trim_end_matches(".sig").trim_end_matches(".db").to_string();
+    }
+    match message.split_once(": ") {
+        Some((repository, _)) if !repository.contains(' ') => repository.to_string(),
+        _ => String::new(),
+    }
+}
@@ -0,0 +1,546 @@
+use super::pacman_conf::{self, PACMAN_CONF};
+use crate::utils;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command as StdCommand;
+use tokio::task;
+
+/// One-line APT source list and its drop-in directory
+const APT_SOURCES_LIST: &str = "/etc/apt/sources.list";
+const APT_SOURCES_DIR: &str = "/etc/apt/sources.list.d";
+
+/// Comment ubuntu-release-upgrader leaves on the sources it turns off,
+/// e.g. "# deb https://... jammy main # disabled on upgrade to noble"
+const UPGRADE_MARKER: &str = "disabled on upgrade to";
+
+/// Archive hosts run by the distributions themselves
+///
+/// Mirrors on other hosts are reported as third-party; the audit errs on the
+/// side of listing a source rather than hiding it.
+const OFFICIAL_APT_HOSTS: &[&str] = &[
+    "archive.ubuntu.com",
+    "*.archive.ubuntu.com",
+    "security.ubuntu.com",
+    "ports.ubuntu.com",
+    "*.ports.ubuntu.com",
+    "esm.ubuntu.com",
+    "archive.canonical.com",
+    "*.debian.org",
+    "apt.pop-os.org",
+];
+
+/// Repositories maintained by Arch Linux and the Arch-based distributions
+const OFFICIAL_PACMAN_REPOSITORIES: &[&str] = &[
+    "core",
+    "extra",
+    "multilib",
+    "core-testing",
+    "extra-testing",
+    "multilib-testing",
+    "gnome-unstable",
+    "kde-unstable",
+    "endeavouros",
+    "cachyos*",
+];
+
+/// Flatpak remote hosts treated as official
+const OFFICIAL_FLATPAK_HOSTS: &[&str] = &["dl.flathub.org", "flathub.org", "registry.fedoraproject.org"];
+
+/// Where a source is configured, which also decides how it is toggled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceKind {
+    /// A `deb` line of sources.list or a `.list` file
+    AptList,
+    /// A stanza of a deb822 `.sources` file
+    AptDeb822,
+    /// A repository section of pacman.conf
+    Pacman,
+    /// A remote of the system-wide Flatpak installation
+    FlatpakSystem,
+    /// A remote of the user's Flatpak installation
+    FlatpakUser,
+}
+
+impl SourceKind {
+    /// Short label shown in the Package Sources page
+    pub fn label(&self) -> &'static str {
+        match self {
+            SourceKind::AptList | SourceKind::AptDeb822 => "APT",
+            SourceKind::Pacman => "pacman",
+            SourceKind::FlatpakSystem => "Flatpak (system)",
+            SourceKind::FlatpakUser => "Flatpak (user)",
+        }
+    }
+}
+
+/// A configured package source: APT archive, pacman repository or Flatpak remote
+#[derive(Debug, Clone)]
+pub struct Source {
+    pub kind: SourceKind,
+    /// "URI suite" for APT, the repository or remote name otherwise
+    pub name: String,
+    /// Components (APT), server or mirrorlist (pacman), URL (Flatpak)
+    pub detail: String,
+    /// File the source is configured in, empty for Flatpak remotes
+    pub file: PathBuf,
+    /// Line (one-line APT format) or stanza (deb822) number in `file`
+    pub index: usize,
+    pub enabled: bool,
+    /// Run by the distribution (or Flathub) rather than a third party
+    pub official: bool,
+    /// Turned off by a distro release upgrade and not re-enabled since
+    pub disabled_by_upgrade: bool,
+}
+
+impl Source {
+    /// Archive URI of an APT source (the first one of a deb822 stanza)
+    pub fn apt_uri(&self) -> Option<&str> {
+        match self.kind {
+            // Names are "URI... suite..."
+            SourceKind::AptList | SourceKind::AptDeb822 => self.name.split_whitespace().next(),
+            _ => None,
+        }
+    }
+
+    /// Check whether an APT source uses the given suite, e.g. "bookworm"
+    pub fn has_suite(&self, suite: &str) -> bool {
+        self.apt_uri().is_some() && self.name.split_whitespace().skip(1).any(|word| word == suite)
+    }
+}
+
+/// List the APT sources and Flatpak remotes
+pub async fn apt_sources() -> Vec<Source> {
+    task::spawn_blocking(|| {
+        let mut sources = read_apt_sources();
+        sources.extend(read_flatpak_remotes());
+        sources
+    })
+    .await
+    .unwrap_or_default()
+}
+
+/// List the pacman repositories (enabled and commented out) and Flatpak remotes
+pub async fn pacman_sources() -> Vec<Source> {
+    task::spawn_blocking(|| {
+        let mut sources = fs::read_to_string(PACMAN_CONF)
+            .map(|contents| parse_pacman_conf(&contents, Path::new(PACMAN_CONF)))
+            .unwrap_or_default();
+        sources.extend(read_flatpak_remotes());
+        sources
+    })
+    .await
+    .unwrap_or_default()
+}
+
+/// Enable or disable a source (through pkexec for system-wide configuration)
+///
+/// APT entries are commented out or uncommented (`Enabled:` for deb822),
+/// pacman repositories have their section commented out, and Flatpak remotes
+/// are changed with `flatpak remote-modify`.
+///
+/// # Arguments
+/// * `source` - Source as listed by `apt_sources` or `pacman_sources`
+/// * `enabled` - Whether the source should be used
+pub async fn set_enabled(source: Source, enabled: bool) -> Result<(), String> {
+    task::spawn_blocking(move || {
+        let flatpak_flag = match source.kind {
+            SourceKind::FlatpakSystem => "--system",
+            SourceKind::FlatpakUser => "--user",
+            _ => return set_enabled_in_file(&source, enabled),
+        };
+
+        let toggle = if enabled { "--enable" } else { "--disable" };
+        let mut command = if source.kind == SourceKind::FlatpakSystem {
+            let mut command = StdCommand::new("pkexec");
+            command.arg("flatpak");
+            command
+        } else {
+            StdCommand::new("flatpak")
+        };
+        let output = command
+            .args(["remote-modify", flatpak_flag, toggle, &source.name])
+            .output()
+            .map_err(|e| format!("Failed to run flatpak: {}", e))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("Changing remote {} failed: {}", source.name, stderr.trim()));
+        }
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Rewrite the configuration file of an APT or pacman source and install it as root
+fn set_enabled_in_file(source: &Source, enabled: bool) -> Result<(), String> {
+    if !is_source_file(source.kind, &source.file) {
+        return Err(format!("{} is not a package source file", source.file.display()));
+    }
+
+    let contents = fs::read_to_string(&source.file)
+        .map_err(|e| format!("Failed to read {}: {}", source.file.display(), e))?;
+
+    let updated = match source.kind {
+        SourceKind::AptList => set_list_line_enabled(&contents, source.index, enabled),
+        SourceKind::AptDeb822 => set_stanza_enabled(&contents, source.index, enabled),
+        _ => pacman_conf::set_repository_enabled(&contents, &source.name, enabled),
+    };
+    let Some(updated) = updated else {
+        return Ok(()); // Already in the requested state
+    };
+
+    // Backups next to APT lists would be picked up as (invalid) source files
+    utils::install_as_root(&updated, &source.file, source.kind == SourceKind::Pacman)
+}
+
+/// Check that a path is one of the files the source readers parse for this kind
+///
+/// Only these are ever installed as root, whatever path a `Source` carries.
+fn is_source_file(kind: SourceKind, file: &Path) -> bool {
+    let in_sources_dir = |extension: &str| {
+        file.parent() == Some(Path::new(APT_SOURCES_DIR))
+            && file.extension().is_some_and(|ext| ext == extension)
+    };
+
+    match kind {
+        SourceKind::AptList => file == Path::new(APT_SOURCES_LIST) || in_sources_dir("list"),
+        SourceKind::AptDeb822 => in_sources_dir("sources"),
+        SourceKind::Pacman => file == Path::new(PACMAN_CONF),
+        SourceKind::FlatpakSystem | SourceKind::FlatpakUser => false,
+    }
+}
+
+/// Read sources.list and the `.list` and `.sources` files of sources.list.d
+pub fn read_apt_sources() -> Vec<Source> {
+    let mut files = vec![PathBuf::from(APT_SOURCES_LIST)];
+    if let Ok(entries) = fs::read_dir(APT_SOURCES_DIR) {
+        let mut dropins: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "list" || ext == "sources"))
+            .collect();
+        dropins.sort();
+        files.extend(dropins);
+    }
+
+    let mut sources = Vec::new();
+    for file in files {
+        let Ok(contents) = fs::read_to_string(&file) else {
+            continue;
+        };
+        if file.extension().is_some_and(|ext| ext == "sources") {
+            sources.extend(parse_deb822(&contents, &file));
+        } else {
+            sources.extend(parse_list(&contents, &file));
+        }
+    }
+    sources
+}
+
+/// A `deb` line of the one-line format, possibly commented out
+struct ListEntry {
+    enabled: bool,
+    disabled_by_upgrade: bool,
+    uri: String,
+    suite: String,
+    components: String,
+}
+
+/// Parse one line of a `.list` file
+///
+/// Accepts `deb [options] URI suite [component...]`, also behind a `#`.
+/// `deb-src` lines do not feed updates and are left out.
+fn parse_list_line(line: &str) -> Option<ListEntry> {
+    let trimmed = line.trim_start();
+    let (enabled, body) = match trimmed.strip_prefix('#') {
+        Some(rest) => (false, rest.trim_start()),
+        None => (true, trimmed),
+    };
+    let (body, comment) = body.split_once('#').unwrap_or((body, ""));
+
+    let mut words: Vec<String> = body.split_whitespace().map(|word| word.to_string()).collect();
+    if words.first().map(|word| word.as_str()) != Some("deb") {
+        return None;
+    }
+    words.remove(0);
+    // "[arch=amd64 signed-by=...]" may span several words
+    if words.first()?.starts_with('[') {
+        let end = words.iter().position(|word| word.ends_with(']'))?;
+        words.drain(..=end);
+    }
+    // "cdrom:[Ubuntu 24.04 LTS ...]/" contains spaces
+    if words.first()?.starts_with("cdrom:[") {
+        let end = words.iter().position(|word| word.contains("]/"))?;
+        let uri = words[..=end].join(" ");
+        words.splice(..=end, [uri]);
+    }
+
+    Some(ListEntry {
+        enabled,
+        disabled_by_upgrade: !enabled && comment.to_lowercase().contains(UPGRADE_MARKER),
+        uri: words.first()?.clone(),
+        suite: words.get(1)?.clone(),
+        components: words.get(2..).unwrap_or_default().join(" "),
+    })
+}
+
+/// List the `deb` lines of a one-line format file
+fn parse_list(contents: &str, file: &Path) -> Vec<Source> {
+    contents
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let entry = parse_list_line(line)?;
+            Some(Source {
+                kind: SourceKind::AptList,
+                name: format!("{} {}", entry.uri, entry.suite),
+                detail: entry.components,
+                file: file.to_path_buf(),
+                index,
+                enabled: entry.enabled,
+                official: is_official_apt_uri(&entry.uri),
+                disabled_by_upgrade: entry.disabled_by_upgrade,
+            })
+        })
+        .collect()
+}
+
+/// Line ranges (start, end exclusive) of the stanzas of a deb822 file
+///
+/// Stanzas are separated by blank lines; blocks holding only comments are not stanzas.
+fn deb822_stanzas(lines: &[&str]) -> Vec<(usize, usize)> {
+    let mut stanzas = Vec::new();
+    let mut start = 0;
+    for end in 0..=lines.len() {
+        if end < lines.len() && !lines[end].trim().is_empty() {
+            continue;
+        }
+        if lines[start..end].iter().any(|line| !line.trim_start().starts_with('#')) {
+            stanzas.push((start, end));
+        }
+        start = end + 1;
+    }
+    stanzas
+}
+
+/// List the `deb` stanzas of a deb822 `.sources` file
+fn parse_deb822(contents: &str, file: &Path) -> Vec<Source> {
+    let lines: Vec<&str> = contents.lines().collect();
+    let mut sources = Vec::new();
+
+    for (index, (start, end)) in deb822_stanzas(&lines).into_iter().enumerate() {
+        let stanza = &lines[start..end];
+        let value = |key: &str| -> String {
+            let mut value = String::new();
+            let mut in_field = false;
+            for line in stanza {
+                if line.trim_start().starts_with('#') {
+                    continue;
+                }
+                if line.starts_with([' ', '\t']) {
+                    // Continuation of a multi-line field
+                    if in_field {
+                        value.push(' ');
+                        value.push_str(line.trim());
+                    }
+                    continue;
+                }
+                in_field = line
+                    .split_once(':')
+                    .is_some_and(|(name, _)| name.trim().eq_ignore_ascii_case(key));
+                if in_field {
+                    value = line.split_once(':').map(|(_, v)| v.trim().to_string()).unwrap_or_default();
+                }
+            }
+            value
+        };
+
+        if !value("Types").split_whitespace().any(|kind| kind == "deb") {
+            continue;
+        }
+        let uris: Vec<String> = value("URIs").split_whitespace().map(|uri| uri.to_string()).collect();
+        let enabled = !value("Enabled").eq_ignore_ascii_case("no");
+        let marked = stanza
+            .iter()
+            .any(|line| line.trim_start().starts_with('#') && line.to_lowercase().contains(UPGRADE_MARKER));
+
+        sources.push(Source {
+            kind: SourceKind::AptDeb822,
+            name: format!("{} {}", uris.join(" "), value("Suites")),
+            detail: value("Components"),
+            file: file.to_path_buf(),
+            index,
+            enabled,
+            official: !uris.is_empty() && uris.iter().all(|uri| is_official_apt_uri(uri)),
+            disabled_by_upgrade: !enabled && marked,
+        });
+    }
+
+    sources
+}
+
+/// Check whether an APT URI points at a distribution's own archive
+fn is_official_apt_uri(uri: &str) -> bool {
+    if uri.starts_with("cdrom:") {
+        return true;
+    }
+    let host = uri_host(uri);
+    OFFICIAL_APT_HOSTS.iter().any(|pattern| utils::glob_match(pattern, host))
+}
+
+/// Host part of a URI, without credentials or port
+fn uri_host(uri: &str) -> &str {
+    let rest = uri.split_once("://").map(|(_, rest)| rest).unwrap_or(uri);
+    let authority = rest.split('/').next().unwrap_or("");
+    let host = authority.rsplit('@').next().unwrap_or(authority);
+    host.split(':').next().unwrap_or(host)
+}
+
+/// Comment out or restore line `index` of a one-line format file
+///
+/// Restoring also drops the "disabled on upgrade to ..." note.
+///
+/// # Returns
+/// * `Some(String)` - The updated file contents
+/// * `None` - The line is not a `deb` line or already in the requested state
+fn set_list_line_enabled(contents: &str, index: usize, enabled: bool) -> Option<String> {
+    let mut lines: Vec<String> = contents.lines().map(|line| line.to_string()).collect();
+    let entry = parse_list_line(lines.get(index)?)?;
+    if entry.enabled == enabled {
+        return None;
+    }
+
+    let line = &lines[index];
+    lines[index] = if enabled {
+        let mut restored = line.trim_start().trim_start_matches('#').trim_start().to_string();
+        if let Some(marker) = restored.to_lowercase().find(UPGRADE_MARKER) {
+            let comment_start = restored[..marker].rfind('#').unwrap_or(marker);
+            restored.truncate(comment_start);
+        }
+        restored.trim_end().to_string()
+    } else {
+        format!("# {}", line)
+    };
+
+    let mut updated = lines.join("\n");
+    updated.push('\n');
+    Some(updated)
+}
+
+/// Set the `Enabled:` field of stanza `index` of a deb822 file
+///
+/// # Returns
+/// * `Some(String)` - The updated file contents
+/// * `None` - No such stanza, or already in the requested state
+fn set_stanza_enabled(contents: &str, index: usize, enabled: bool) -> Option<String> {
+    let mut lines: Vec<String> = contents.lines().map(|line| line.to_string()).collect();
+    let borrowed: Vec<&str> = lines.iter().map(|line| line.as_str()).collect();
+    let (start, end) = *deb822_stanzas(&borrowed).get(index)?;
+
+    let field = (start..end).find(|&i| {
+        lines[i]
+            .split_once(':')
+            .is_some_and(|(name, _)| !name.starts_with('#') && name.trim().eq_ignore_ascii_case("Enabled"))
+    });
+    let currently_enabled = field.is_none_or(|i| {
+        !lines[i].split_once(':').is_some_and(|(_, value)| value.trim().eq_ignore_ascii_case("no"))
+    });
+    if currently_enabled == enabled {
+        return None;
+    }
+
+    match field {
+        Some(i) => lines[i] = format!("Enabled: {}", if enabled { "yes" } else { "no" }),
+        None => lines.insert(end, "Enabled: no".to_string()),
+    }
+
+    let mut updated = lines.join("\n");
+    updated.push('\n');
+    Some(updated)
+}
+
+/// List the repository sections of pacman.conf, including commented-out ones
+///
+/// Repositories defined in included files are not listed; pacman.conf is
+/// where they are switched on and off.
+fn parse_pacman_conf(contents: &str, file: &Path) -> Vec<Source> {
+    let lines: Vec<&str> = contents.lines().collect();
+    let mut sources = Vec::new();
+
+    for (index, line) in lines.iter().enumerate() {
+        let Some((enabled, name)) = pacman_conf::repository_header(line) else {
+            continue;
+        };
+        if name == "options" {
+            continue;
+        }
+
+        // First Server or Include of the section tells where packages come from
+        let detail = lines[index + 1..]
+            .iter()
+            .take_while(|line| !line.trim().is_empty() && pacman_conf::repository_header(line).is_none())
+            .find_map(|line| {
+                let (key, value) = line.trim().trim_start_matches('#').split_once('=')?;
+                matches!(key.trim(), "Server" | "Include").then(|| value.trim().to_string())
+            })
+            .unwrap_or_default();
+
+        sources.push(Source {
+            kind: SourceKind::Pacman,
+            name: name.to_string(),
+            detail,
+            file: file.to_path_buf(),
+            index,
+            enabled,
+            official: OFFICIAL_PACMAN_REPOSITORIES.iter().any(|pattern| utils::glob_match(pattern, name)),
+            // Arch has no release upgrades
+            disabled_by_upgrade: false,
+        });
+    }
+
+    sources
+}
+
+/// List the remotes of the system and user Flatpak installations
+///
+/// Returns nothing when Flatpak is not installed.
+fn read_flatpak_remotes() -> Vec<Source> {
+    let Ok(output) = StdCommand::new("flatpak")
+        .args(["remotes", "--show-disabled", "--columns=name,url,options"])
+        .output()
+    else {
+        return Vec::new();
+    };
+    if !output.status.success() {
+        return Vec::new();
+    }
+
+    // Tab-separated, without a header when not printing to a terminal
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut columns = line.split('\t');
+            let name = columns.next()?.trim();
+            let url = columns.next().unwrap_or("").trim();
+            let options: Vec<&str> = columns.next().unwrap_or("").split(',').map(|o| o.trim()).collect();
+            if name.is_empty() {
+                return None;
+            }
+            Some(Source {
+                kind: if options.contains(&"user") {
+                    SourceKind::FlatpakUser
+                } else {
+                    SourceKind::FlatpakSystem
+                },
+                name: name.to_string(),
+                detail: url.to_string(),
+                file: PathBuf::new(),
+                index: 0,
+                enabled: !options.contains(&"disabled"),
+                official: OFFICIAL_FLATPAK_HOSTS.contains(&uri_host(url)),
+                disabled_by_upgrade: false,
+            })
+        })
+        .collect()
+}
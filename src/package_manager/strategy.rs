@@ -0,0 +1,171 @@
+use super::aur_helper::AurHelper;
+use serde::{Deserialize, Serialize};
+
+/// Extra flags accepted for `apt upgrade` / `apt full-upgrade`
+const APT_FLAGS: &[&str] = &[
+    "--no-install-recommends",
+    "--with-new-pkgs",
+    "--without-new-pkgs",
+    "--verbose-versions",
+    "-V",
+    "--show-upgraded",
+    "--quiet",
+    "-q",
+];
+
+/// Extra flags accepted for `pacman -Syu` (entries ending in '=' take a value)
+const PACMAN_FLAGS: &[&str] = &[
+    "--needed",
+    "--verbose",
+    "-v",
+    "--color=",
+    "--disable-download-timeout",
+    "--ignore=",
+    "--ignoregroup=",
+];
+
+/// How the Upgrade button runs each backend
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UpgradeStrategy {
+    /// APT: run `full-upgrade` (may install new and remove packages) instead of `upgrade`
+    #[serde(default)]
+    pub apt_full_upgrade: bool,
+    /// APT: answer yes to prompts (`-y`)
+    #[serde(default = "default_true")]
+    pub apt_assume_yes: bool,
+    /// APT: extra flags, checked against an allow-list
+    #[serde(default)]
+    pub apt_extra_flags: String,
+    /// pacman: skip confirmation prompts (`--noconfirm`)
+    #[serde(default = "default_true")]
+    pub pacman_noconfirm: bool,
+    /// pacman: extra flags, checked against an allow-list
+    #[serde(default)]
+    pub pacman_extra_flags: String,
+    /// AUR helper: skip confirmation prompts (`--noconfirm`)
+    #[serde(default)]
+    pub aur_noconfirm: bool,
+    /// AUR helper: also rebuild VCS (-git) packages (`--devel`)
+    #[serde(default)]
+    pub aur_devel: bool,
+    /// AUR helper: only upgrade repository packages (`--repo`)
+    #[serde(default)]
+    pub aur_skip: bool,
+    /// AUR helper: extra flags, checked against an allow-list
+    #[serde(default)]
+    pub aur_extra_flags: String,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for UpgradeStrategy {
+    fn default() -> Self {
+        Self {
+            apt_full_upgrade: false,
+            apt_assume_yes: true,
+            apt_extra_flags: String::new(),
+            pacman_noconfirm: true,
+            pacman_extra_flags: String::new(),
+            aur_noconfirm: false,
+            aur_devel: false,
+            aur_skip: false,
+            aur_extra_flags: String::new(),
+        }
+    }
+}
+
+impl UpgradeStrategy {
+    /// Command line for the APT upgrade
+    pub fn apt_command(&self) -> Result<Vec<String>, String> {
+        let mut command = vec!["pkexec".to_string(), "apt".to_string()];
+        command.push(if self.apt_full_upgrade { "full-upgrade" } else { "upgrade" }.to_string());
+        if self.apt_assume_yes {
+            command.push("-y".to_string());
+        }
+        command.extend(check_flags(&self.apt_extra_flags, &[APT_FLAGS])?);
+        Ok(command)
+    }
+
+    /// Command line for the pacman upgrade
+    pub fn pacman_command(&self) -> Result<Vec<String>, String> {
+        let mut command = vec!["pkexec".to_string(), "pacman".to_string(), "-Syu".to_string()];
+        if self.pacman_noconfirm {
+            command.push("--noconfirm".to_string());
+        }
+        command.extend(check_flags(&self.pacman_extra_flags, &[PACMAN_FLAGS])?);
+        Ok(command)
+    }
+
+    /// Command line for an AUR helper upgrade
+    ///
+    /// Helpers that cannot skip the AUR fall back to the pacman upgrade when
+    /// `aur_skip` is set, and helpers that only upgrade the AUR are chained
+    /// after it with `&&`.
+    ///
+    /// # Arguments
+    /// * `helper` - Helper table entry, e.g. the one for paru
+    pub fn aur_command(&self, helper: &AurHelper) -> Result<Vec<String>, String> {
+        let extra_flags = if helper.passes_pacman_flags() {
+            check_flags(&self.aur_extra_flags, &[PACMAN_FLAGS, helper.allowed_flags()])?
+        } else {
+            check_flags(&self.aur_extra_flags, &[helper.allowed_flags()])?
+        };
+        let Some(mut command) = helper.upgrade_command(self.aur_devel, self.aur_skip, self.aur_noconfirm) else {
+            return self.pacman_command();
+        };
+        command.extend(extra_flags);
+
+        if helper.upgrades_repositories_separately() {
+            let mut chained = self.pacman_command()?;
+            chained.push("&&".to_string());
+            chained.extend(command);
+            return Ok(chained);
+        }
+        Ok(command)
+    }
+
+    /// Check every backend's extra flags against the allow-lists
+    ///
+    /// # Arguments
+    /// * `helper` - The AUR helper the settings select, whose own allow-list applies
+    pub fn validate(&self, helper: Option<&AurHelper>) -> Result<(), String> {
+        self.apt_command()?;
+        self.pacman_command()?;
+        if let Some(helper) = helper {
+            self.aur_command(helper)?;
+        }
+        Ok(())
+    }
+}
+
+/// Split user-provided flags and check each one against the allow-lists
+///
+/// Allow-list entries ending in '=' accept a value, which is limited to
+/// characters that are safe on a shell command line.
+fn check_flags(flags: &str, allowed: &[&[&str]]) -> Result<Vec<String>, String> {
+    flags
+        .split_whitespace()
+        .map(|flag| {
+            let permitted = allowed.iter().flat_map(|list| list.iter()).any(|entry| {
+                if entry.ends_with('=') {
+                    flag.strip_prefix(entry).is_some_and(|value| {
+                        !value.is_empty()
+                            && value
+                                .chars()
+                                .all(|c| c.is_ascii_alphanumeric() || "+-._,".contains(c))
+                    })
+                } else {
+                    flag == *entry
+                }
+            });
+
+            if permitted {
+                Ok(flag.to_string())
+            } else {
+                Err(format!("Flag not allowed: {}", flag))
+            }
+        })
+        .collect()
+}
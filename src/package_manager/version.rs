@@ -0,0 +1,305 @@
+use std::cmp::Ordering;
+
+/// Version string format used by a backend
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scheme {
+    /// `[epoch:]upstream[-revision]`, compared like `dpkg --compare-versions`
+    Debian,
+    /// `[epoch:]pkgver-pkgrel`, compared like pacman's `vercmp`
+    Pacman,
+}
+
+/// How far apart the installed and the new version of an update are
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Bump {
+    /// Versions could not be classified
+    #[default]
+    Unknown,
+    /// The "new" version is older than the installed one (e.g. an outdated mirror)
+    Downgrade,
+    /// The epoch changed, usually after upstream changed its versioning scheme
+    Epoch,
+    /// First upstream component changed (1.x → 2.x)
+    Major,
+    /// Second upstream component changed (1.2 → 1.3)
+    Minor,
+    /// A later upstream component changed (1.2.3 → 1.2.4)
+    Patch,
+    /// Same upstream version, new packaging revision (pkgrel / Debian revision)
+    Revision,
+}
+
+impl Bump {
+    /// Short label shown as a badge next to the version
+    pub fn label(&self) -> &'static str {
+        match self {
+            Bump::Unknown => "",
+            Bump::Downgrade => "downgrade",
+            Bump::Epoch => "epoch",
+            Bump::Major => "major",
+            Bump::Minor => "minor",
+            Bump::Patch => "patch",
+            Bump::Revision => "rebuild",
+        }
+    }
+
+    /// Bumps that deserve attention even when minor updates are not notified
+    pub fn is_significant(&self) -> bool {
+        matches!(self, Bump::Downgrade | Bump::Epoch | Bump::Major)
+    }
+}
+
+/// A version split into its epoch, upstream version and packaging revision
+struct Parts<'a> {
+    epoch: &'a str,
+    upstream: &'a str,
+    revision: Option<&'a str>,
+}
+
+/// Split `[epoch:]upstream[-revision]`
+///
+/// The epoch is only recognised when everything before the first ':' is a
+/// number, and the revision starts after the last '-'.
+fn split(version: &str) -> Parts<'_> {
+    let (epoch, rest) = match version.split_once(':') {
+        Some((epoch, rest)) if !epoch.is_empty() && epoch.bytes().all(|b| b.is_ascii_digit()) => (epoch, rest),
+        _ => ("0", version),
+    };
+
+    match rest.rsplit_once('-') {
+        Some((upstream, revision)) => Parts {
+            epoch,
+            upstream,
+            revision: Some(revision),
+        },
+        None => Parts {
+            epoch,
+            upstream: rest,
+            revision: None,
+        },
+    }
+}
+
+/// Compare two versions according to the backend's rules
+pub fn compare(scheme: Scheme, a: &str, b: &str) -> Ordering {
+    match scheme {
+        Scheme::Debian => compare_debian(a, b),
+        Scheme::Pacman => compare_pacman(a, b),
+    }
+}
+
+/// Compare two Debian versions with the same result as `dpkg --compare-versions`
+pub fn compare_debian(a: &str, b: &str) -> Ordering {
+    let (a, b) = (split(a), split(b));
+
+    let epoch_a: u64 = a.epoch.parse().unwrap_or(0);
+    let epoch_b: u64 = b.epoch.parse().unwrap_or(0);
+
+    epoch_a
+        .cmp(&epoch_b)
+        .then_with(|| debian_part_cmp(a.upstream, b.upstream))
+        .then_with(|| debian_part_cmp(a.revision.unwrap_or(""), b.revision.unwrap_or("")))
+}
+
+/// Sort weight of a character in dpkg's non-digit comparison
+///
+/// '~' sorts before everything (even the end of the string), letters sort
+/// before other characters.
+fn debian_order(c: Option<u8>) -> i32 {
+    match c {
+        None => 0,
+        Some(c) if c.is_ascii_digit() => 0,
+        Some(c) if c.is_ascii_alphabetic() => c as i32,
+        Some(b'~') => -1,
+        Some(c) => c as i32 + 256,
+    }
+}
+
+/// dpkg's `verrevcmp`: alternate non-digit and digit runs
+fn debian_part_cmp(a: &str, b: &str) -> Ordering {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    let (mut i, mut j) = (0, 0);
+
+    while i < a.len() || j < b.len() {
+        // Non-digit prefix, compared character by character
+        while (i < a.len() && !a[i].is_ascii_digit()) || (j < b.len() && !b[j].is_ascii_digit()) {
+            let ac = debian_order(a.get(i).copied());
+            let bc = debian_order(b.get(j).copied());
+            if ac != bc {
+                return ac.cmp(&bc);
+            }
+            i += 1;
+            j += 1;
+        }
+
+        // Numeric run, compared by value
+        while i < a.len() && a[i] == b'0' {
+            i += 1;
+        }
+        while j < b.len() && b[j] == b'0' {
+            j += 1;
+        }
+
+        let mut first_diff = Ordering::Equal;
+        while i < a.len() && a[i].is_ascii_digit() && j < b.len() && b[j].is_ascii_digit() {
+            if first_diff == Ordering::Equal {
+                first_diff = a[i].cmp(&b[j]);
+            }
+            i += 1;
+            j += 1;
+        }
+        if i < a.len() && a[i].is_ascii_digit() {
+            return Ordering::Greater;
+        }
+        if j < b.len() && b[j].is_ascii_digit() {
+            return Ordering::Less;
+        }
+        if first_diff != Ordering::Equal {
+            return first_diff;
+        }
+    }
+
+    Ordering::Equal
+}
+
+/// Compare two pacman versions with the same result as `vercmp`
+///
+/// The release (pkgrel) is only compared when both versions have one.
+pub fn compare_pacman(a: &str, b: &str) -> Ordering {
+    let (a, b) = (split(a), split(b));
+
+    rpm_vercmp(a.epoch, b.epoch)
+        .then_with(|| rpm_vercmp(a.upstream, b.upstream))
+        .then_with(|| match (a.revision, b.revision) {
+            (Some(rel_a), Some(rel_b)) => rpm_vercmp(rel_a, rel_b),
+            _ => Ordering::Equal,
+        })
+}
+
+/// libalpm's `rpmvercmp`: compare alphanumeric segments separated by other characters
+fn rpm_vercmp(a: &str, b: &str) -> Ordering {
+    if a == b {
+        return Ordering::Equal;
+    }
+
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    let (mut one, mut two) = (0, 0);
+    let (mut ptr1, mut ptr2) = (0, 0);
+
+    while one < a.len() && two < b.len() {
+        while one < a.len() && !a[one].is_ascii_alphanumeric() {
+            one += 1;
+        }
+        while two < b.len() && !b[two].is_ascii_alphanumeric() {
+            two += 1;
+        }
+        if one >= a.len() || two >= b.len() {
+            break;
+        }
+
+        // A longer separator run makes the version newer
+        if one - ptr1 != two - ptr2 {
+            return (one - ptr1).cmp(&(two - ptr2));
+        }
+
+        ptr1 = one;
+        ptr2 = two;
+
+        let is_num = a[ptr1].is_ascii_digit();
+        if is_num {
+            while ptr1 < a.len() && a[ptr1].is_ascii_digit() {
+                ptr1 += 1;
+            }
+            while ptr2 < b.len() && b[ptr2].is_ascii_digit() {
+                ptr2 += 1;
+            }
+        } else {
+            while ptr1 < a.len() && a[ptr1].is_ascii_alphabetic() {
+                ptr1 += 1;
+            }
+            while ptr2 < b.len() && b[ptr2].is_ascii_alphabetic() {
+                ptr2 += 1;
+            }
+        }
+
+        // Segments of different types: numbers are newer than letters
+        if two == ptr2 {
+            return if is_num { Ordering::Greater } else { Ordering::Less };
+        }
+
+        let seg1 = &a[one..ptr1];
+        let seg2 = &b[two..ptr2];
+        let ordering = if is_num {
+            let seg1 = trim_leading_zeros(seg1);
+            let seg2 = trim_leading_zeros(seg2);
+            seg1.len().cmp(&seg2.len()).then_with(|| seg1.cmp(seg2))
+        } else {
+            seg1.cmp(seg2)
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+
+        one = ptr1;
+        two = ptr2;
+    }
+
+    if one >= a.len() && two >= b.len() {
+        return Ordering::Equal;
+    }
+
+    // A remaining alpha segment never beats an empty string ("1.0alpha" < "1.0"),
+    // anything else left over makes that version newer
+    let a_alpha = a.get(one).is_some_and(|c| c.is_ascii_alphabetic());
+    let b_alpha = b.get(two).is_some_and(|c| c.is_ascii_alphabetic());
+    if (one >= a.len() && !b_alpha) || a_alpha {
+        Ordering::Less
+    } else {
+        Ordering::Greater
+    }
+}
+
+fn trim_leading_zeros(digits: &[u8]) -> &[u8] {
+    let start = digits.iter().position(|&d| d != b'0').unwrap_or(digits.len());
+    &digits[start..]
+}
+
+/// Classify the change from the installed to the new version
+///
+/// Upstream versions are split into components at any character that is not a
+/// letter or digit, and the position of the first differing component decides
+/// between major, minor and patch.
+pub fn classify(scheme: Scheme, current: &str, new: &str) -> Bump {
+    if current.is_empty() || new.is_empty() {
+        return Bump::Unknown;
+    }
+
+    match compare(scheme, new, current) {
+        Ordering::Less => return Bump::Downgrade,
+        Ordering::Equal => return Bump::Revision,
+        Ordering::Greater => {}
+    }
+
+    let (old, new) = (split(current), split(new));
+    if compare(scheme, old.epoch, new.epoch) != Ordering::Equal {
+        return Bump::Epoch;
+    }
+    if compare(scheme, old.upstream, new.upstream) == Ordering::Equal {
+        return Bump::Revision;
+    }
+
+    let old_components: Vec<&str> = old.upstream.split(|c: char| !c.is_ascii_alphanumeric()).collect();
+    let new_components: Vec<&str> = new.upstream.split(|c: char| !c.is_ascii_alphanumeric()).collect();
+
+    let first_change = old_components
+        .iter()
+        .zip(&new_components)
+        .position(|(a, b)| compare(scheme, a, b) != Ordering::Equal)
+        .unwrap_or_else(|| old_components.len().min(new_components.len()));
+
+    match first_change {
+        0 => Bump::Major,
+        1 => Bump::Minor,
+        _ => Bump::Patch,
+    }
+}
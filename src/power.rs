@@ -0,0 +1,212 @@
+use crate::config::Config;
+use std::fs;
+use std::path::Path;
+use std::process::Command as StdCommand;
+use tokio::task;
+
+/// Location of the kernel's power supply class in sysfs
+const POWER_SUPPLY_DIR: &str = "/sys/class/power_supply";
+
+/// Load average file used for idle detection
+const LOADAVG_PATH: &str = "/proc/loadavg";
+
+/// Fraction of the available CPUs the 1-minute load may use while still counting as idle
+const IDLE_LOAD_PER_CPU: f64 = 0.5;
+
+/// Snapshot of the machine's power, network and load situation
+///
+/// Used to decide whether scheduled checks, background downloads and upgrades
+/// should run right now or be deferred until conditions improve.
+#[derive(Debug, Clone)]
+pub struct PowerStatus {
+    /// True when running on mains power (or when no system battery exists)
+    pub on_ac: bool,
+    /// Average charge of the system batteries, if any are present
+    pub battery_percent: Option<u32>,
+    /// True when NetworkManager reports the primary connection as metered
+    pub metered: bool,
+    /// True when the 1-minute load average is low enough for background work
+    pub idle: bool,
+}
+
+impl Default for PowerStatus {
+    fn default() -> Self {
+        Self {
+            on_ac: true,
+            battery_percent: None,
+            metered: false,
+            idle: true,
+        }
+    }
+}
+
+impl PowerStatus {
+    /// Reason a scheduled update check should be skipped, if any
+    pub fn check_deferral(&self, config: &Config) -> Option<String> {
+        if self.metered && config.skip_checks_on_metered {
+            return Some("Scheduled check skipped: metered connection".to_string());
+        }
+        None
+    }
+
+    /// Reason an upgrade should be refused, if any
+    ///
+    /// Upgrades are always allowed on AC power. On battery they are refused when the
+    /// charge is below the configured minimum (a minimum of 0 disables the guard).
+    pub fn upgrade_deferral(&self, config: &Config) -> Option<String> {
+        if self.on_ac || config.min_battery_percent == 0 {
+            return None;
+        }
+        match self.battery_percent {
+            Some(percent) if percent < config.min_battery_percent => Some(format!(
+                "Upgrade deferred: battery at {}% (minimum {}% unless on AC power)",
+                percent, config.min_battery_percent
+            )),
+            _ => None,
+        }
+    }
+
+    /// Reason a background download should be postponed, if any
+    ///
+    /// Background downloads only run on AC power while the machine is idle, and
+    /// optionally never on metered connections.
+    pub fn download_deferral(&self, config: &Config) -> Option<String> {
+        if self.metered && config.defer_downloads_on_metered {
+            return Some("Download deferred: metered connection".to_string());
+        }
+        if !self.on_ac {
+            return Some("Download deferred: running on battery".to_string());
+        }
+        if !self.idle {
+            return Some("Download deferred: system is busy".to_string());
+        }
+        None
+    }
+}
+
+/// Read the current power and connection status
+///
+/// Battery state comes from `/sys/class/power_supply`, the metered flag from
+/// NetworkManager over D-Bus. Missing information is treated permissively
+/// (assume AC power and an unmetered connection) so that machines without a
+/// battery or without NetworkManager behave exactly as before.
+pub async fn read_status() -> PowerStatus {
+    task::spawn_blocking(|| {
+        let mut status = read_power_supply(Path::new(POWER_SUPPLY_DIR));
+        status.metered = read_metered();
+        status.idle = read_idle();
+        status
+    })
+    .await
+    .unwrap_or_default()
+}
+
+/// Read AC and battery state from the sysfs power supply class
+fn read_power_supply(dir: &Path) -> PowerStatus {
+    let mut mains_online = false;
+    let mut has_mains = false;
+    let mut battery_discharging = false;
+    let mut capacities = Vec::new();
+
+    let Ok(entries) = fs::read_dir(dir) else {
+        return PowerStatus::default();
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let supply_type = read_attr(&path, "type").unwrap_or_default();
+
+        match supply_type.as_str() {
+            "Mains" | "USB" => {
+                has_mains = true;
+                if read_attr(&path, "online").as_deref() == Some("1") {
+                    mains_online = true;
+                }
+            }
+            "Battery" => {
+                // Ignore peripheral batteries (mice, keyboards, headsets)
+                if read_attr(&path, "scope").as_deref() == Some("Device") {
+                    continue;
+                }
+                if let Some(capacity) = read_attr(&path, "capacity").and_then(|c| c.parse::<u32>().ok()) {
+                    capacities.push(capacity.min(100));
+                }
+                if read_attr(&path, "status").as_deref() == Some("Discharging") {
+                    battery_discharging = true;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if capacities.is_empty() {
+        // Desktop machine without a system battery
+        return PowerStatus::default();
+    }
+
+    let battery_percent = capacities.iter().sum::<u32>() / capacities.len() as u32;
+    // Some laptops do not expose a mains supply, fall back to the battery status
+    let on_ac = if has_mains { mains_online } else { !battery_discharging };
+
+    PowerStatus {
+        on_ac,
+        battery_percent: Some(battery_percent),
+        ..PowerStatus::default()
+    }
+}
+
+/// Read a single trimmed sysfs attribute
+fn read_attr(supply: &Path, attr: &str) -> Option<String> {
+    fs::read_to_string(supply.join(attr))
+        .ok()
+        .map(|value| value.trim().to_string())
+}
+
+/// Ask NetworkManager whether the primary connection is metered
+///
+/// NetworkManager exposes an `NMMetered` value: 1 (yes) and 3 (guess-yes) count
+/// as metered, everything else (unknown, no, guess-no) does not.
+fn read_metered() -> bool {
+    let output = StdCommand::new("busctl")
+        .args([
+            "get-property",
+            "org.freedesktop.NetworkManager",
+            "/org/freedesktop/NetworkManager",
+            "org.freedesktop.NetworkManager",
+            "Metered",
+        ])
+        .output();
+
+    match output {
+        Ok(output) if output.status.success() => {
+            parse_metered(&String::from_utf8_lossy(&output.stdout))
+        }
+        _ => false,
+    }
+}
+
+/// Parse busctl property output of the form "u 1"
+fn parse_metered(output: &str) -> bool {
+    matches!(
+        output.split_whitespace().nth(1).and_then(|v| v.parse::<u32>().ok()),
+        Some(1) | Some(3)
+    )
+}
+
+/// Check whether the system load is low enough to start background work
+fn read_idle() -> bool {
+    let cpus = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1) as f64;
+
+    fs::read_to_string(LOADAVG_PATH)
+        .ok()
+        .and_then(|contents| {
+            contents
+                .split_whitespace()
+                .next()
+                .and_then(|load| load.parse::<f64>().ok())
+        })
+        .map(|load| load < cpus * IDLE_LOAD_PER_CPU)
+        .unwrap_or(true)
+}
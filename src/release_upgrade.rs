@@ -0,0 +1,323 @@
+use crate::package_manager::{apt_db, sources};
+use crate::utils;
+use std::collections::HashMap;
+use std::fs;
+use std::process::Command;
+use tokio::task;
+
+/// os-release locations, the first one present wins
+const OS_RELEASE: &[&str] = &["/etc/os-release", "/usr/lib/os-release"];
+
+/// Release and end-of-life dates shipped by distro-info-data
+const DISTRO_INFO_DIR: &str = "/usr/share/distro-info";
+
+/// ubuntu-release-upgrader configuration: upgrade policy and meta-release URLs
+const RELEASE_UPGRADES_CONF: &str = "/etc/update-manager/release-upgrades";
+const META_RELEASE_CONF: &str = "/etc/update-manager/meta-release";
+
+/// Meta-release lists used when the configuration does not name one
+const DEFAULT_META_RELEASE: &str = "https://changelogs.ubuntu.com/meta-release";
+const DEFAULT_META_RELEASE_LTS: &str = "https://changelogs.ubuntu.com/meta-release-lts";
+
+/// Debian mirror used when no Debian source is configured
+const DEFAULT_DEBIAN_MIRROR: &str = "https://deb.debian.org/debian";
+
+/// A new distribution release, or the end of support for the installed one
+#[derive(Debug, Clone, Default)]
+pub struct ReleaseUpgrade {
+    /// Installed release, e.g. "Ubuntu 22.04.4 LTS"
+    pub current: String,
+    /// Release that can be upgraded to, e.g. "24.04.1 LTS", empty if none is offered yet
+    pub available: String,
+    /// The installed release no longer receives security updates
+    pub end_of_life: bool,
+    /// End of support of the installed release (YYYY-MM-DD), empty if unknown
+    pub eol_date: String,
+    /// Release notes of the new release
+    pub release_notes: String,
+    /// Upgrade instructions for the distribution
+    pub guide: String,
+    /// Shell command running the distribution's upgrade tool, empty if the upgrade is manual
+    pub command: String,
+}
+
+/// Check for a new release of Ubuntu, Pop!_OS or Debian
+///
+/// Ubuntu asks `do-release-upgrade -c`, which follows the "Prompt" policy of
+/// the release upgrader, and Pop!_OS asks `pop-upgrade release check`. Debian
+/// compares the installed major version with the current `stable` release of
+/// the configured mirror. All of them also look up the end of support of the
+/// installed release in distro-info-data.
+///
+/// # Returns
+/// * `Ok(Some(ReleaseUpgrade))` - A new release is available or the installed one reached end of life
+/// * `Ok(None)` - Nothing to report, or the distribution is not supported (rolling releases)
+/// * `Err(String)` - The mirror could not be asked
+pub async fn check() -> Result<Option<ReleaseUpgrade>, String> {
+    task::spawn_blocking(|| {
+        let os_release = read_os_release();
+        let id = os_release.get("ID").map(|id| id.as_str()).unwrap_or("");
+        let id_like = os_release.get("ID_LIKE").map(|id| id.as_str()).unwrap_or("");
+
+        let upgrade = if id == "pop" {
+            check_pop(&os_release)
+        } else if id == "ubuntu" || id_like.split_whitespace().any(|like| like == "ubuntu") {
+            check_ubuntu(&os_release)
+        } else if id == "debian" {
+            check_debian(&os_release)?
+        } else {
+            return Ok(None);
+        };
+
+        Ok((!upgrade.available.is_empty() || upgrade.end_of_life).then_some(upgrade))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Launch the distribution's upgrade tool in a terminal emulator
+///
+/// # Arguments
+/// * `terminal` - Terminal emulator to use
+/// * `upgrade` - The `command` of the release upgrade
+pub async fn run_upgrade(terminal: String, upgrade: String) -> Result<(), String> {
+    task::spawn_blocking(move || {
+        let command = format!("{}; echo ''; echo 'Press Enter to close...'; read", upgrade);
+        Command::new(&terminal)
+            .args(["-e", "bash", "-c", &command])
+            .spawn()
+            .map_err(|e| format!("Failed to launch terminal '{}': {}", terminal, e))?;
+
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Ask the Ubuntu release upgrader for a new release
+///
+/// A missing or failing `do-release-upgrade` only means no new release is
+/// reported; the end-of-life check still applies.
+fn check_ubuntu(os_release: &HashMap<String, String>) -> ReleaseUpgrade {
+    // Derivatives name the Ubuntu base separately
+    let codename = os_release
+        .get("UBUNTU_CODENAME")
+        .or(os_release.get("VERSION_CODENAME"))
+        .cloned()
+        .unwrap_or_default();
+
+    // Prints "New release '24.04.1 LTS' available." and exits 0 when there is one
+    let available = Command::new("do-release-upgrade")
+        .arg("-c")
+        .env("LC_ALL", "C")
+        .output()
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .find(|line| line.starts_with("New release"))
+                .and_then(|line| line.split('\'').nth(1))
+                .unwrap_or("")
+                .to_string()
+        })
+        .unwrap_or_default();
+
+    let release_notes = if available.is_empty() {
+        String::new()
+    } else {
+        ubuntu_release_notes(&available).unwrap_or_else(|| "https://wiki.ubuntu.com/Releases".to_string())
+    };
+
+    let eol_date = end_of_life_date("ubuntu", &codename).unwrap_or_default();
+    ReleaseUpgrade {
+        current: os_release.get("PRETTY_NAME").cloned().unwrap_or_default(),
+        command: if available.is_empty() { String::new() } else { "pkexec do-release-upgrade".to_string() },
+        available,
+        end_of_life: is_past(&eol_date),
+        eol_date,
+        release_notes,
+        guide: "https://help.ubuntu.com/community/Upgrades".to_string(),
+    }
+}
+
+/// Ask System76's upgrade daemon for a new Pop!_OS release
+///
+/// Pop!_OS releases are only upgraded through `pop-upgrade`; Ubuntu's release
+/// upgrader would move the system to the next Ubuntu release instead. Without
+/// `pop-upgrade` no release is detected and upgrades follow the guide.
+fn check_pop(os_release: &HashMap<String, String>) -> ReleaseUpgrade {
+    let codename = os_release
+        .get("UBUNTU_CODENAME")
+        .or(os_release.get("VERSION_CODENAME"))
+        .cloned()
+        .unwrap_or_default();
+
+    // Prints "Current Release: 22.04", "Upgrading To: 24.04" and
+    // "New Release Available: true" (exact labels vary between versions)
+    let available = Command::new("pop-upgrade")
+        .args(["release", "check"])
+        .env("LC_ALL", "C")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| {
+            let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+            let fields: Vec<(String, String)> = stdout
+                .lines()
+                .filter_map(|line| {
+                    let (key, value) = line.split_once(':')?;
+                    Some((key.trim().to_lowercase(), value.trim().to_string()))
+                })
+                .collect();
+            let value = |matches: &dyn Fn(&str) -> bool| {
+                fields.iter().find(|(key, _)| matches(key)).map(|(_, value)| value.clone())
+            };
+            let current = value(&|key| key.contains("current")).unwrap_or_default();
+            let next = value(&|key| key.contains("upgrad") && !key.contains("available")).unwrap_or_default();
+            let offered = value(&|key| key.contains("available")).map(|flag| flag == "true");
+            if offered.unwrap_or(!next.is_empty() && next != current) {
+                next
+            } else {
+                String::new()
+            }
+        })
+        .unwrap_or_default();
+
+    let eol_date = end_of_life_date("ubuntu", &codename).unwrap_or_default();
+    ReleaseUpgrade {
+        current: os_release.get("PRETTY_NAME").cloned().unwrap_or_default(),
+        command: if available.is_empty() { String::new() } else { "pop-upgrade release upgrade".to_string() },
+        available,
+        end_of_life: is_past(&eol_date),
+        eol_date,
+        release_notes: String::new(),
+        guide: "https://support.system76.com/articles/upgrade-pop/".to_string(),
+    }
+}
+
+/// Find the release notes of an Ubuntu release in the meta-release list
+///
+/// The list is the one do-release-upgrade reads: LTS-only when the "Prompt"
+/// policy is `lts`, with URLs from /etc/update-manager/meta-release.
+fn ubuntu_release_notes(version: &str) -> Option<String> {
+    let lts_only = fs::read_to_string(RELEASE_UPGRADES_CONF)
+        .map(|contents| contents.lines().any(|line| line.replace(' ', "") == "Prompt=lts"))
+        .unwrap_or(false);
+    let key = if lts_only { "URI_LTS" } else { "URI" };
+    let url = fs::read_to_string(META_RELEASE_CONF)
+        .ok()
+        .and_then(|contents| {
+            contents.lines().find_map(|line| {
+                let (name, value) = line.split_once('=')?;
+                (name.trim() == key).then(|| value.trim().to_string())
+            })
+        })
+        .unwrap_or_else(|| {
+            if lts_only { DEFAULT_META_RELEASE_LTS } else { DEFAULT_META_RELEASE }.to_string()
+        });
+
+    let meta_release = utils::fetch_url(&url).ok()?;
+    let mut notes = None;
+    apt_db::for_each_stanza(&meta_release, |fields| {
+        if apt_db::field(fields, "Version") == Some(version) {
+            notes = apt_db::field(fields, "ReleaseNotes").map(|url| url.to_string());
+        }
+    });
+    notes
+}
+
+/// Compare the installed Debian release with `stable` on the configured mirror
+///
+/// Testing and unstable systems (no numeric /etc/debian_version) are never
+/// offered an upgrade.
+fn check_debian(os_release: &HashMap<String, String>) -> Result<ReleaseUpgrade, String> {
+    let codename = os_release.get("VERSION_CODENAME").cloned().unwrap_or_default();
+    let eol_date = end_of_life_date("debian", &codename).unwrap_or_default();
+    let mut upgrade = ReleaseUpgrade {
+        current: os_release.get("PRETTY_NAME").cloned().unwrap_or_default(),
+        end_of_life: is_past(&eol_date),
+        eol_date,
+        guide: "https://wiki.debian.org/DebianUpgrade".to_string(),
+        ..Default::default()
+    };
+
+    // "12.11" on stable releases, "trixie/sid" on testing and unstable
+    let installed_major = fs::read_to_string("/etc/debian_version")
+        .ok()
+        .and_then(|version| version.trim().split('.').next()?.parse::<u32>().ok());
+    let Some(installed_major) = installed_major else {
+        return Ok(upgrade);
+    };
+
+    let mirror = sources::read_apt_sources()
+        .into_iter()
+        .find(|source| source.enabled && source.official && source.has_suite(&codename))
+        .and_then(|source| source.apt_uri().map(|uri| uri.trim_end_matches('/').to_string()))
+        .unwrap_or_else(|| DEFAULT_DEBIAN_MIRROR.to_string());
+
+    let release = match utils::fetch_url(&format!("{}/dists/stable/Release", mirror)) {
+        Ok(contents) => apt_db::parse_release(&contents),
+        // The end of life is known without the mirror and worth reporting on its own
+        Err(e) if upgrade.end_of_life => {
+            eprintln!("Warning: Could not check for a new Debian release: {}", e);
+            return Ok(upgrade);
+        }
+        Err(e) => return Err(e),
+    };
+    let stable_major = release.version.split('.').next().and_then(|major| major.parse::<u32>().ok());
+    if stable_major.is_some_and(|major| major > installed_major) {
+        upgrade.available = format!("Debian {} ({})", release.version, release.codename);
+        upgrade.release_notes = format!("https://www.debian.org/releases/{}/releasenotes", release.codename);
+    }
+
+    Ok(upgrade)
+}
+
+/// Read os-release into a map, with quotes removed from the values
+fn read_os_release() -> HashMap<String, String> {
+    let contents = OS_RELEASE
+        .iter()
+        .find_map(|path| fs::read_to_string(path).ok())
+        .unwrap_or_default();
+
+    contents
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            Some((key.trim().to_string(), value.trim().trim_matches('"').trim_matches('\'').to_string()))
+        })
+        .collect()
+}
+
+/// Look up the end of support of a release in distro-info-data
+///
+/// Debian releases count as supported until the end of Debian LTS, Ubuntu
+/// releases until the end of standard support.
+///
+/// # Arguments
+/// * `distro` - "debian" or "ubuntu" (name of the CSV file)
+/// * `series` - Release codename, e.g. "bookworm" or "jammy"
+fn end_of_life_date(distro: &str, series: &str) -> Option<String> {
+    let csv = fs::read_to_string(format!("{}/{}.csv", DISTRO_INFO_DIR, distro)).ok()?;
+    let mut rows = csv.lines();
+    let header: Vec<&str> = rows.next()?.split(',').collect();
+    let column = |name: &str| header.iter().position(|column| *column == name);
+    let series_column = column("series")?;
+    let eol_column = column("eol")?;
+    let lts_column = if distro == "debian" { column("eol-lts") } else { None };
+
+    let row: Vec<&str> = rows
+        .map(|row| row.split(',').collect::<Vec<&str>>())
+        .find(|row| row.get(series_column) == Some(&series))?;
+
+    lts_column
+        .and_then(|index| row.get(index))
+        .filter(|date| !date.is_empty())
+        .or_else(|| row.get(eol_column))
+        .filter(|date| !date.is_empty())
+        .map(|date| date.to_string())
+}
+
+/// Check whether a YYYY-MM-DD date lies in the past
+fn is_past(date: &str) -> bool {
+    !date.is_empty() && chrono::Local::now().format("%Y-%m-%d").to_string().as_str() > date
+}
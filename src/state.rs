@@ -2,6 +2,26 @@ use crate::utils;
 use serde::{Deserialize, Serialize};
 use std::fs;
 
+/// Maximum number of history entries kept in the state file
+const MAX_HISTORY_ENTRIES: usize = 50;
+
+/// Record of an action the applet performed on its own
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// Unix timestamp (seconds) when the action finished
+    pub timestamp: i64,
+    /// Short description of the action (e.g. "Unattended security upgrade")
+    pub action: String,
+    /// Packages affected, formatted as "name old -> new"
+    #[serde(default)]
+    pub packages: Vec<String>,
+    /// Whether the action succeeded
+    pub success: bool,
+    /// Error message or summary
+    #[serde(default)]
+    pub message: String,
+}
+
 /// Persistent state for tracking notification behavior
 ///
 /// Stores information between app restarts to enable intelligent notification logic,
@@ -9,7 +29,36 @@ use std::fs;
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct State {
     /// Number of available updates from the last check
+    #[serde(default)]
     pub last_update_count: usize,
+    /// Number of pending security updates from the last check
+    #[serde(default)]
+    pub last_security_count: usize,
+    /// Number of VCS package updates from the last check
+    #[serde(default)]
+    pub last_vcs_count: usize,
+    /// Finish time (Unix seconds) of the last offline update result shown to the user
+    #[serde(default)]
+    pub last_offline_result_seen: u64,
+    /// Unix timestamp (seconds) of the last unattended security upgrade attempt
+    #[serde(default)]
+    pub last_unattended_attempt: i64,
+    /// Unix timestamp (seconds) of the last upgrade launched from the applet
+    #[serde(default)]
+    pub last_upgrade_time: i64,
+    /// guid of the newest Arch news item the user acknowledged before upgrading
+    #[serde(default)]
+    pub last_acknowledged_news: String,
+    /// Publication time (Unix seconds) of that acknowledged news item
+    #[serde(default)]
+    pub last_acknowledged_news_date: i64,
+    /// Pending updates already reported, as "name new_version", so only newly
+    /// found ones count toward a notification (also after a restart)
+    #[serde(default)]
+    pub notified_updates: Vec<String>,
+    /// Actions the applet performed without user interaction, newest last
+    #[serde(default)]
+    pub history: Vec<HistoryEntry>,
 }
 
 impl State {
@@ -33,6 +82,15 @@ impl State {
         Self::default()
     }
 
+    /// Append a history entry, dropping the oldest ones beyond the limit
+    pub fn record(&mut self, entry: HistoryEntry) {
+        self.history.push(entry);
+        if self.history.len() > MAX_HISTORY_ENTRIES {
+            let excess = self.history.len() - MAX_HISTORY_ENTRIES;
+            self.history.drain(..excess);
+        }
+    }
+
     /// Save state to file
     pub fn save(&self) -> Result<(), String> {
         let path = Self::state_path().ok_or("Could not determine config directory")?;
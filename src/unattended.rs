@@ -0,0 +1,98 @@
+use crate::config::Config;
+use crate::package_manager::{Package, PackageManager};
+use crate::state::{HistoryEntry, State};
+use chrono::{Local, Timelike};
+use std::process::Command as StdCommand;
+use tokio::task;
+
+/// Privileged helper binary, authorized through the polkit action shipped in `data/`
+const HELPER_PATH: &str = "/usr/libexec/cosmic-ext-applet-updates/cosmic-ext-applet-updates-helper";
+
+/// Minimum time between two unattended attempts (seconds)
+const RETRY_INTERVAL_SECS: i64 = 60 * 60;
+
+/// Check whether the backend can install security updates on their own
+///
+/// Only APT can: Arch does not support partial upgrades, so the pacman
+/// backends refuse to upgrade a subset of the pending updates.
+pub fn is_supported(pm: &PackageManager) -> bool {
+    matches!(pm, PackageManager::Apt(_))
+}
+
+/// Decide whether an unattended security upgrade should run now
+///
+/// Requires a supported backend, the feature to be enabled, the local time to
+/// be inside the maintenance window, at least one pending security update and
+/// no attempt within the last hour (so a failing upgrade is not retried on
+/// every check).
+pub fn is_due(pm: &PackageManager, config: &Config, state: &State, packages: &[Package]) -> bool {
+    if !is_supported(pm) || !config.unattended_security_updates {
+        return false;
+    }
+
+    let now = Local::now();
+    if !config.in_maintenance_window(now.hour()) {
+        return false;
+    }
+
+    if now.timestamp() - state.last_unattended_attempt < RETRY_INTERVAL_SECS {
+        return false;
+    }
+
+    packages.iter().any(|p| p.is_security && !p.is_aur)
+}
+
+/// Run the privileged helper to install pending security updates
+///
+/// The helper decides on its own which packages qualify, the applet only
+/// triggers it and records the outcome.
+///
+/// # Returns
+/// * `Ok(HistoryEntry)` - The helper ran, entry describes what it did
+/// * `Err(String)` - The helper could not be started
+pub async fn run_security_upgrade() -> Result<HistoryEntry, String> {
+    task::spawn_blocking(|| {
+        let output = StdCommand::new("pkexec")
+            .args([HELPER_PATH, "security-upgrade"])
+            .output()
+            .map_err(|e| format!("Failed to run update helper: {}", e))?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let packages = parse_helper_output(&stdout);
+        let success = output.status.success();
+
+        let message = if success {
+            format!(
+                "{} security update{} installed",
+                packages.len(),
+                if packages.len() == 1 { "" } else { "s" }
+            )
+        } else {
+            String::from_utf8_lossy(&output.stderr).trim().to_string()
+        };
+
+        Ok(HistoryEntry {
+            timestamp: Local::now().timestamp(),
+            action: "Unattended security upgrade".to_string(),
+            packages,
+            success,
+            message,
+        })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Parse helper output lines of the form "upgraded name old new"
+fn parse_helper_output(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            match parts.as_slice() {
+                ["upgraded", name, old, new] => Some(format!("{} {} -> {}", name, old, new)),
+                _ => None,
+            }
+        })
+        .collect()
+}
@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 /// Get the path to an application file, checking the new location first and falling back to the old location
@@ -96,3 +96,173 @@ pub fn get_terminal(preference: &str) -> String {
         preference.to_string()
     }
 }
+
+/// Fetch a document from a URL or read it from a local file
+///
+/// Sources starting with `/` or `file://` are read from disk, which lets feeds and
+/// API responses be served from a local mirror or a test fixture. Everything else
+/// is downloaded with curl.
+///
+/// # Arguments
+/// * `source` - URL, `file://` URL or absolute path
+///
+/// # Returns
+/// * `Ok(String)` - Document contents
+/// * `Err(String)` - Reading or downloading failed
+pub fn fetch_url(source: &str) -> Result<String, String> {
+    let local_path = source.strip_prefix("file://").unwrap_or(source);
+    if local_path.starts_with('/') {
+        return std::fs::read_to_string(local_path)
+            .map_err(|e| format!("Failed to read {}: {}", local_path, e));
+    }
+
+    let output = Command::new("curl")
+        .args(["--fail", "--silent", "--show-error", "--location", "--max-time", "30"])
+        .arg(source)
+        .output()
+        .map_err(|e| format!("Failed to run curl: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Failed to fetch {}: {}", source, stderr.trim()));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Match a name against a shell-style glob pattern
+///
+/// Supports `*` (any run of characters, including none) and `?` (exactly one
+/// character). Everything else matches literally.
+///
+/// # Arguments
+/// * `pattern` - Glob pattern, e.g. "linux-*" or "nvidia*"
+/// * `name` - Name to test
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    let (mut p, mut n) = (0, 0);
+    // Position of the last '*' and the name position it was tried at
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, n));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            // Let the last '*' swallow one more character and retry
+            p = star + 1;
+            n = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Format a byte count for display, e.g. "12.3 MiB"
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+/// Replace a root-owned configuration file through pkexec
+///
+/// The new contents are written to a file only the user can read, in a fresh
+/// private directory, and copied into place with `install -m 0644`, so only
+/// the copy needs privileges and nobody can swap the file before root reads it.
+/// Symlinked targets are refused rather than replaced by a regular file.
+///
+/// # Arguments
+/// * `contents` - New file contents
+/// * `target` - File to replace, e.g. "/etc/pacman.conf"
+/// * `backup` - Keep the previous version as `<target>~`
+pub fn install_as_root(contents: &str, target: &Path, backup: bool) -> Result<(), String> {
+    if !target.is_absolute() || target.symlink_metadata().is_ok_and(|meta| !meta.file_type().is_file()) {
+        return Err(format!("Refusing to replace {}: not a regular file", target.display()));
+    }
+
+    let temp_dir = create_private_dir()?;
+    let temp_path = temp_dir.join("contents");
+    if let Err(e) = write_new_file(&temp_path, contents) {
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        return Err(format!("Failed to write {}: {}", temp_path.display(), e));
+    }
+
+    let mut command = Command::new("pkexec");
+    command.arg("install");
+    if backup {
+        command.arg("-b");
+    }
+    let output = command
+        .args(["-m", "0644"])
+        .arg(&temp_path)
+        .arg(target)
+        .output()
+        .map_err(|e| format!("Failed to run pkexec: {}", e));
+
+    let _ = std::fs::remove_dir_all(&temp_dir);
+    let output = output?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Updating {} failed: {}", target.display(), stderr));
+    }
+
+    Ok(())
+}
+
+/// Create a new directory readable only by the user
+///
+/// The directory lives in `$XDG_RUNTIME_DIR` (falling back to the temp dir)
+/// and must not exist yet, so a planted directory or symlink makes this fail
+/// instead of being used.
+fn create_private_dir() -> Result<PathBuf, String> {
+    use std::os::unix::fs::DirBuilderExt;
+
+    let base = std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_dir())
+        .unwrap_or_else(std::env::temp_dir);
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.subsec_nanos())
+        .unwrap_or(0);
+    let dir = base.join(format!("cosmic-ext-applet-updates-{}-{}", std::process::id(), nanos));
+
+    std::fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+    Ok(dir)
+}
+
+/// Write a file that must not exist yet, readable only by the user
+fn write_new_file(path: &Path, contents: &str) -> std::io::Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)?;
+    file.write_all(contents.as_bytes())
+}